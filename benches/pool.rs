@@ -3,7 +3,7 @@ extern crate criterion;
 
 use criterion::Criterion;
 use lifeguard::{StartingSize, Supplier};
-use swimmer::Pool;
+use swimmer::{Pool, Storage};
 
 fn pool(c: &mut Criterion) {
     let pool: Pool<Vec<u8>> = swimmer::builder()
@@ -13,6 +13,24 @@ fn pool(c: &mut Criterion) {
     c.bench_function("pool", move |b| b.iter(|| pool.get()));
 }
 
+fn pool_global(c: &mut Criterion) {
+    let pool: Pool<Vec<u8>> = swimmer::builder()
+        .with_starting_size(1024)
+        .with_supplier(|| Vec::with_capacity(1024))
+        .with_storage(Storage::Global)
+        .build();
+    c.bench_function("pool_global", move |b| b.iter(|| pool.get()));
+}
+
+fn pool_sharded(c: &mut Criterion) {
+    let pool: Pool<Vec<u8>> = swimmer::builder()
+        .with_starting_size(1024)
+        .with_supplier(|| Vec::with_capacity(1024))
+        .with_storage(Storage::Sharded(8))
+        .build();
+    c.bench_function("pool_sharded", move |b| b.iter(|| pool.get()));
+}
+
 fn alloc(c: &mut Criterion) {
     c.bench_function("alloc", move |b| b.iter(|| Vec::<u8>::with_capacity(1024)));
 }
@@ -24,5 +42,5 @@ fn lifeguard(c: &mut Criterion) {
         .build();
     c.bench_function("lifeguard", move |b| b.iter(|| pool.new()));
 }
-criterion_group!(benches, pool, alloc, lifeguard);
+criterion_group!(benches, pool, pool_global, pool_sharded, alloc, lifeguard);
 criterion_main!(benches);
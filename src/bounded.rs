@@ -0,0 +1,508 @@
+#[cfg(feature = "stats")]
+use crate::PoolStats;
+use crate::{builder, CreateError, Pool, PoolBuilder, Recyclable, Recycled, Storage};
+use std::collections::VecDeque;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::future::Future;
+use std::mem::{self, ManuallyDrop};
+use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
+
+/// An object pool which limits the number
+/// of objects which can be checked out at once.
+///
+/// When all objects are checked out, retrieving
+/// an object waits until another one is returned,
+/// rather than initializing a new object. This makes
+/// the pool suitable for expensive objects, such as
+/// database clients.
+///
+/// Objects can be retrieved by blocking the
+/// current thread (`get`, `try_get` and `get_timeout`) or
+/// asynchronously (`get_async`). The returned future
+/// works with any executor.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use swimmer::BoundedPool;
+///
+/// let pool: BoundedPool<String> = BoundedPool::new(1);
+///
+/// let string = pool.get();
+/// assert!(pool.get_timeout(Duration::from_millis(1)).is_none());
+///
+/// drop(string);
+/// assert!(pool.get_timeout(Duration::from_millis(1)).is_some());
+/// ```
+pub struct BoundedPool<T>
+where
+    T: Recyclable,
+{
+    pool: Pool<T>,
+    capacity: usize,
+    state: Mutex<State>,
+    available: Condvar,
+}
+
+struct State {
+    checked_out: usize,
+    /// The tasks waiting in `get_async`, in the
+    /// order in which they started waiting.
+    waiters: VecDeque<Waiter>,
+    next_waiter: u64,
+}
+
+/// The slot of a pending `Checkout`.
+///
+/// Each future has at most one slot, which is
+/// removed once its task is woken.
+struct Waiter {
+    id: u64,
+    waker: Waker,
+}
+
+impl State {
+    /// Removes the slot with the given id, returning
+    /// whether it was still waiting to be woken.
+    fn remove_waiter(&mut self, id: u64) -> bool {
+        match self.waiters.iter().position(|waiter| waiter.id == id) {
+            Some(index) => {
+                self.waiters.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl<T> BoundedPool<T>
+where
+    T: Recyclable,
+{
+    /// Creates a new bounded pool with default
+    /// settings, allowing at most `capacity`
+    /// objects to be checked out at once.
+    ///
+    /// This is equivalent to `swimmer::builder().build_bounded(capacity)`.
+    ///
+    /// # Panics
+    /// Panics if `capacity` is 0.
+    pub fn new(capacity: usize) -> BoundedPool<T> {
+        builder().build_bounded(capacity)
+    }
+
+    pub(crate) fn from_pool(pool: Pool<T>, capacity: usize) -> BoundedPool<T> {
+        assert!(capacity > 0, "capacity must be nonzero");
+        BoundedPool {
+            pool,
+            capacity,
+            state: Mutex::new(State {
+                checked_out: 0,
+                waiters: VecDeque::new(),
+                next_waiter: 0,
+            }),
+            available: Condvar::new(),
+        }
+    }
+
+    /// Retrieves a value from the pool, blocking
+    /// the current thread until one is available.
+    pub fn get(&self) -> BoundedRecycled<'_, T> {
+        self.wait();
+        self.checkout()
+    }
+
+    /// Like `get`, but returns an error rather than
+    /// panicking if a new object has to be created
+    /// and the pool's supplier fails.
+    ///
+    /// The object which failed to be created
+    /// doesn't count towards the capacity.
+    ///
+    /// # Examples
+    /// ```
+    /// use swimmer::BoundedPool;
+    ///
+    /// let pool: BoundedPool<String> = swimmer::builder()
+    ///     .with_try_supplier(|| Err("no connection"))
+    ///     .build_bounded(1);
+    ///
+    /// assert!(pool.try_get().is_err());
+    /// assert_eq!(pool.available(), 1);
+    /// ```
+    pub fn try_get(&self) -> Result<BoundedRecycled<'_, T>, CreateError> {
+        self.wait();
+        self.try_checkout()
+    }
+
+    /// Retrieves a value from the pool, blocking
+    /// the current thread until one is available
+    /// or `timeout` elapses.
+    ///
+    /// Returns `None` if the timeout elapsed.
+    pub fn get_timeout(&self, timeout: Duration) -> Option<BoundedRecycled<'_, T>> {
+        let deadline = Instant::now() + timeout;
+
+        let mut state = self.state();
+        while !self.try_acquire(&mut state) {
+            let now = Instant::now();
+            if now >= deadline {
+                return None;
+            }
+
+            state = self
+                .available
+                .wait_timeout(state, deadline - now)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+        drop(state);
+
+        Some(self.checkout())
+    }
+
+    /// Retrieves a value from the pool asynchronously.
+    ///
+    /// The returned future resolves once
+    /// a value is available.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::future::Future;
+    /// # use std::sync::Arc;
+    /// # use std::task::{Context, Poll, Wake, Waker};
+    /// use swimmer::BoundedPool;
+    /// # struct Noop;
+    /// # impl Wake for Noop {
+    /// #     fn wake(self: Arc<Self>) {}
+    /// # }
+    /// # fn block_on<F: Future>(future: F) -> F::Output {
+    /// #     let waker = Waker::from(Arc::new(Noop));
+    /// #     let mut cx = Context::from_waker(&waker);
+    /// #     let mut future = Box::pin(future);
+    /// #     loop {
+    /// #         if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+    /// #             return output;
+    /// #         }
+    /// #     }
+    /// # }
+    ///
+    /// let pool: BoundedPool<Vec<u8>> = BoundedPool::new(4);
+    ///
+    /// let vec = block_on(async { pool.get_async().await });
+    /// assert_eq!(pool.available(), 3);
+    /// ```
+    pub fn get_async(&self) -> Checkout<'_, T> {
+        Checkout {
+            pool: self,
+            waiter: None,
+        }
+    }
+
+    /// Returns the maximum number of objects
+    /// which can be checked out at once.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of objects which can
+    /// currently be checked out without waiting.
+    pub fn available(&self) -> usize {
+        self.capacity - self.state().checked_out
+    }
+
+    /// Returns the number of idle objects in the pool.
+    ///
+    /// See `Pool::size`.
+    pub fn size(&self) -> usize {
+        self.pool.size()
+    }
+
+    /// Returns a snapshot of the pool's statistics.
+    ///
+    /// See `Pool::stats`.
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> PoolStats {
+        self.pool.stats()
+    }
+
+    fn try_acquire(&self, state: &mut State) -> bool {
+        if state.checked_out < self.capacity {
+            state.checked_out += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Blocks the current thread until an object
+    /// can be checked out, and acquires it.
+    fn wait(&self) {
+        let mut state = self.state();
+        while !self.try_acquire(&mut state) {
+            state = self
+                .available
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    /// Retrieves the object for an acquired slot.
+    fn checkout(&self) -> BoundedRecycled<'_, T> {
+        // Release the slot if the supplier panics.
+        let permit = Permit { pool: self };
+        let value = self.pool.get();
+        mem::forget(permit);
+
+        BoundedRecycled {
+            value: ManuallyDrop::new(value),
+            pool: self,
+        }
+    }
+
+    /// Like `checkout`, but releases the slot
+    /// if the object can't be created.
+    fn try_checkout(&self) -> Result<BoundedRecycled<'_, T>, CreateError> {
+        let permit = Permit { pool: self };
+        let value = self.pool.try_get()?;
+        mem::forget(permit);
+
+        Ok(BoundedRecycled {
+            value: ManuallyDrop::new(value),
+            pool: self,
+        })
+    }
+
+    fn release(&self) {
+        let mut state = self.state();
+        state.checked_out -= 1;
+        Self::notify(&mut state);
+        drop(state);
+
+        self.available.notify_one();
+    }
+
+    /// Wakes the task which has been waiting the
+    /// longest, removing its slot.
+    fn notify(state: &mut State) {
+        if let Some(waiter) = state.waiters.pop_front() {
+            waiter.waker.wake();
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T> PoolBuilder<T>
+where
+    T: Recyclable,
+{
+    /// Builds a bounded pool using the configured
+    /// settings, allowing at most `capacity` objects
+    /// to be checked out at once.
+    ///
+    /// Returned objects must be available to any waiting
+    /// thread, so threads don't keep their own buffers:
+    /// `Storage::ThreadLocal` is replaced by `Storage::Global`,
+    /// and `with_max_size` has no effect.
+    ///
+    /// # Panics
+    /// Panics if `capacity` is 0.
+    pub fn build_bounded(self, capacity: usize) -> BoundedPool<T> {
+        let builder = if self.storage.is_thread_local() {
+            self.with_storage(Storage::Global)
+        } else {
+            self
+        };
+        BoundedPool::from_pool(builder.build(), capacity)
+    }
+}
+
+/// A future which resolves to a value retrieved
+/// from a `BoundedPool`.
+///
+/// Objects of this type are obtained using `BoundedPool::get_async`.
+pub struct Checkout<'a, T>
+where
+    T: Recyclable,
+{
+    pool: &'a BoundedPool<T>,
+    /// The id of the slot registered by this future,
+    /// which has been removed if the task was woken.
+    waiter: Option<u64>,
+}
+
+impl<'a, T> Future for Checkout<'a, T>
+where
+    T: Recyclable,
+{
+    type Output = BoundedRecycled<'a, T>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let pool = self.pool;
+
+        let mut state = pool.state();
+        if pool.try_acquire(&mut state) {
+            if let Some(id) = self.waiter.take() {
+                state.remove_waiter(id);
+            }
+            drop(state);
+            return Poll::Ready(pool.checkout());
+        }
+
+        // Update the waker in this future's slot rather
+        // than adding another one, so that each pending
+        // future is only woken once per release.
+        let waker = cx.waker();
+        let slot = self
+            .waiter
+            .and_then(|id| state.waiters.iter_mut().find(|waiter| waiter.id == id));
+        match slot {
+            Some(waiter) => {
+                if !waiter.waker.will_wake(waker) {
+                    waiter.waker = waker.clone();
+                }
+            }
+            None => {
+                let id = state.next_waiter;
+                state.next_waiter += 1;
+                state.waiters.push_back(Waiter {
+                    id,
+                    waker: waker.clone(),
+                });
+                self.waiter = Some(id);
+            }
+        }
+        Poll::Pending
+    }
+}
+
+impl<'a, T> Drop for Checkout<'a, T>
+where
+    T: Recyclable,
+{
+    fn drop(&mut self) {
+        if let Some(id) = self.waiter {
+            let mut state = self.pool.state();
+            // If this future was woken but is dropped before
+            // acquiring an object, the wakeup would be lost.
+            // Pass it on to the next waiting task instead.
+            if !state.remove_waiter(id) && state.checked_out < self.pool.capacity {
+                BoundedPool::<T>::notify(&mut state);
+            }
+        }
+    }
+}
+
+/// An acquired slot which is released
+/// unless it is forgotten.
+struct Permit<'a, T>
+where
+    T: Recyclable,
+{
+    pool: &'a BoundedPool<T>,
+}
+
+impl<'a, T> Drop for Permit<'a, T>
+where
+    T: Recyclable,
+{
+    fn drop(&mut self) {
+        self.pool.release();
+    }
+}
+
+/// A smart pointer which returns the contained
+/// object to its `BoundedPool` once dropped, allowing
+/// another object to be checked out.
+///
+/// Objects of this type are obtained using `BoundedPool::get`,
+/// `BoundedPool::try_get`, `BoundedPool::get_timeout` and
+/// `BoundedPool::get_async`.
+pub struct BoundedRecycled<'a, T>
+where
+    T: Recyclable,
+{
+    value: ManuallyDrop<Recycled<'a, T>>,
+    pool: &'a BoundedPool<T>,
+}
+
+impl<'a, T> Drop for BoundedRecycled<'a, T>
+where
+    T: Recyclable,
+{
+    fn drop(&mut self) {
+        // Return the value to the pool before
+        // releasing it, so that the next waiter
+        // can retrieve it.
+        unsafe {
+            // Safe because `self.value` is never
+            // accessed again after this point.
+            ManuallyDrop::drop(&mut self.value);
+        }
+
+        self.pool.release();
+    }
+}
+
+impl<'a, T> AsRef<T> for BoundedRecycled<'a, T>
+where
+    T: Recyclable,
+{
+    fn as_ref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<'a, T> AsMut<T> for BoundedRecycled<'a, T>
+where
+    T: Recyclable,
+{
+    fn as_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+impl<'a, T> Deref for BoundedRecycled<'a, T>
+where
+    T: Recyclable,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<'a, T> DerefMut for BoundedRecycled<'a, T>
+where
+    T: Recyclable,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.value
+    }
+}
+
+impl<'a, T> Display for BoundedRecycled<'a, T>
+where
+    T: Recyclable + Display,
+{
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.as_ref())
+    }
+}
+
+impl<'a, T> Debug for BoundedRecycled<'a, T>
+where
+    T: Recyclable + Debug,
+{
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{:?}", self.as_ref())
+    }
+}
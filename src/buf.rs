@@ -0,0 +1,230 @@
+use crate::stats::Event;
+use crate::{builder, KeyedPool, Pool, PoolBuilder, Recyclable};
+use std::fmt::{self, Debug, Formatter};
+use std::mem::{self, ManuallyDrop};
+use std::ops::{Deref, DerefMut};
+
+/// A pool of byte buffers, sorted into
+/// buckets by their capacity.
+///
+/// Each bucket holds buffers whose capacity lies
+/// between two consecutive powers of two. `get` takes a
+/// buffer from the smallest bucket which is guaranteed
+/// to satisfy the requested capacity, and once dropped,
+/// the buffer goes to the bucket matching its actual
+/// capacity—even if it grew while it was used.
+///
+/// Buffers are recycled using the `Recyclable`
+/// implementation for `Vec`, so they are empty
+/// when retrieved.
+///
+/// # Examples
+/// ```
+/// use swimmer::BufPool;
+/// let pool = BufPool::new();
+///
+/// let mut buf = pool.get(1000);
+/// assert!(buf.capacity() >= 1000);
+/// buf.extend_from_slice(b"hello");
+/// drop(buf);
+///
+/// // The buffer has a capacity of 1024,
+/// // so it is reused for requests up to 1024 bytes
+/// let buf = pool.get(600);
+/// assert_eq!(buf.capacity(), 1024);
+/// assert!(buf.is_empty());
+/// ```
+pub struct BufPool {
+    /// The pools for each bucket, keyed by the
+    /// base-2 logarithm of their smallest capacity.
+    buckets: KeyedPool<u32, Vec<u8>>,
+}
+
+impl BufPool {
+    /// Creates a new buffer pool with
+    /// default settings for each bucket.
+    pub fn new() -> BufPool {
+        BufPool::with_builder(|_| builder())
+    }
+
+    /// Creates a new buffer pool, using the builders
+    /// returned by `factory` to configure the pool for
+    /// each bucket. The function is called with the
+    /// smallest capacity of the bucket's buffers.
+    ///
+    /// Any supplier and recycler set on the builders is
+    /// replaced, since the buckets rely on buffers keeping
+    /// their capacity while they are in the pool.
+    ///
+    /// # Examples
+    /// ```
+    /// use swimmer::BufPool;
+    ///
+    /// // Keep fewer buffers of larger sizes
+    /// let pool = BufPool::with_builder(|capacity| {
+    ///     swimmer::builder().with_max_total_size((1 << 20) / capacity.max(1024))
+    /// });
+    /// ```
+    pub fn with_builder<F>(factory: F) -> BufPool
+    where
+        F: Fn(usize) -> PoolBuilder<Vec<u8>> + Send + Sync + 'static,
+    {
+        BufPool {
+            buckets: KeyedPool::new(move |&bucket: &u32| {
+                let capacity = 1 << bucket;
+                factory(capacity)
+                    .with_supplier(move || Vec::with_capacity(capacity))
+                    .with_recycler(<Vec<u8> as Recyclable>::recycle)
+            }),
+        }
+    }
+
+    /// Retrieves an empty buffer with a
+    /// capacity of at least `min_capacity`.
+    ///
+    /// # Panics
+    /// Panics if `min_capacity` is larger than
+    /// the largest power of two of `usize`.
+    pub fn get(&self, min_capacity: usize) -> BufRecycled<'_> {
+        let capacity = min_capacity
+            .checked_next_power_of_two()
+            .expect("capacity overflow");
+        let bucket = capacity.trailing_zeros();
+
+        let mut buf = self.buckets.pool(&bucket).get_raw_value();
+        // Hooks may have shrunk the buffer
+        // after its bucket was chosen.
+        buf.reserve(min_capacity);
+
+        BufRecycled {
+            buf: ManuallyDrop::new(buf),
+            pool: self,
+            bucket,
+        }
+    }
+
+    /// Returns the pool holding the buffers
+    /// with the given minimum capacity.
+    ///
+    /// # Panics
+    /// Panics if `capacity` is not a power of two.
+    pub fn bucket(&self, capacity: usize) -> &Pool<Vec<u8>> {
+        assert!(capacity.is_power_of_two(), "not a power of two");
+        self.buckets.pool(&capacity.trailing_zeros())
+    }
+
+    /// Returns the total number of buffers in the pool.
+    pub fn size(&self) -> usize {
+        self.buckets.size()
+    }
+
+    /// Returns `buf`, which was retrieved from
+    /// the given bucket, to the pool.
+    fn return_buf(&self, buf: Vec<u8>, bucket: u32) {
+        // Buffers without capacity have no bucket;
+        // there is no point in keeping them anyway.
+        if buf.capacity() == 0 {
+            self.buckets.pool(&bucket).discard_returned(buf);
+            return;
+        }
+
+        let bucket = usize::BITS - 1 - buf.capacity().leading_zeros();
+        self.buckets.pool(&bucket).return_value(buf, None);
+    }
+}
+
+impl Default for BufPool {
+    fn default() -> Self {
+        BufPool::new()
+    }
+}
+
+/// A smart pointer which returns the contained buffer
+/// to its `BufPool` once dropped, into the bucket
+/// matching the buffer's capacity.
+///
+/// Objects of this type are obtained using `BufPool::get`.
+pub struct BufRecycled<'a> {
+    buf: ManuallyDrop<Vec<u8>>,
+    pool: &'a BufPool,
+    /// The bucket the buffer was retrieved from.
+    bucket: u32,
+}
+
+impl<'a> BufRecycled<'a> {
+    /// Takes ownership of the contained buffer
+    /// without returning it to the pool.
+    ///
+    /// See `Recycled::into_inner`.
+    ///
+    /// # Examples
+    /// ```
+    /// use swimmer::{BufPool, BufRecycled};
+    /// let pool = BufPool::new();
+    ///
+    /// let buf: Vec<u8> = BufRecycled::into_inner(pool.get(16));
+    /// drop(buf);
+    /// assert_eq!(pool.size(), 0);
+    /// ```
+    pub fn into_inner(mut this: Self) -> Vec<u8> {
+        this.pool.buckets.pool(&this.bucket).record(Event::Detach);
+
+        let buf = unsafe {
+            // Safe because `this` is forgotten
+            // without running its destructor.
+            ManuallyDrop::take(&mut this.buf)
+        };
+        mem::forget(this);
+        buf
+    }
+
+    /// Returns the pool which the buffer
+    /// will be returned to.
+    pub fn pool(this: &Self) -> &'a BufPool {
+        this.pool
+    }
+}
+
+impl<'a> Drop for BufRecycled<'a> {
+    fn drop(&mut self) {
+        let buf = unsafe {
+            // Safe because `self.buf` is never
+            // accessed again after this point.
+            ManuallyDrop::take(&mut self.buf)
+        };
+
+        self.pool.return_buf(buf, self.bucket);
+    }
+}
+
+impl<'a> AsRef<[u8]> for BufRecycled<'a> {
+    fn as_ref(&self) -> &[u8] {
+        &self.buf
+    }
+}
+
+impl<'a> AsMut<[u8]> for BufRecycled<'a> {
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.buf
+    }
+}
+
+impl<'a> Deref for BufRecycled<'a> {
+    type Target = Vec<u8>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.buf
+    }
+}
+
+impl<'a> DerefMut for BufRecycled<'a> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.buf
+    }
+}
+
+impl<'a> Debug for BufRecycled<'a> {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{:?}", &*self.buf)
+    }
+}
@@ -1,5 +1,5 @@
-use crate::{init, Pool, Recyclable};
-use thread_local::CachedThreadLocal;
+use crate::{CreateError, Pool, Recyclable, ReturnPolicy, Storage};
+use std::time::Duration;
 
 /// Creates a new `PoolBuilder`, used
 /// to initialize a `Pool`.
@@ -17,11 +17,54 @@ where
 /// new objects for a pool.
 pub type Supplier<T> = dyn Fn() -> T + Send + Sync;
 
+/// A fallible supplier function, used to initialize
+/// new objects for a pool.
+pub type TrySupplier<T> = dyn Fn() -> Result<T, CreateError> + Send + Sync;
+
+/// A recycler function, used to reset
+/// objects returned to a pool.
+pub type Recycler<T> = dyn Fn(&mut T) + Send + Sync;
+
+/// A validator function, used to decide whether
+/// an object returned to a pool may be reused.
+pub type Validator<T> = dyn Fn(&T) -> bool + Send + Sync;
+
+/// A hook function, called with an object
+/// at some point in its lifecycle.
+pub type Hook<T> = dyn Fn(&mut T) + Send + Sync;
+
+/// A shard key function, used to choose the shard
+/// of a pool using `Storage::Sharded`.
+pub type ShardKey = dyn Fn() -> usize + Send + Sync;
+
+/// The panic message used when an infallible
+/// function fails to initialize an object.
+pub(crate) const CREATE_FAILED: &str = "failed to initialize pooled object";
+
+/// The default value for `PoolBuilder::with_max_size`.
+const DEFAULT_MAX_SIZE: usize = 64;
+
 /// A pool builder, used to configure various
 /// pool settings.
 pub struct PoolBuilder<T: Recyclable> {
     pub(crate) starting_size: usize,
-    pub(crate) supplier: Option<Box<Supplier<T>>>,
+    pub(crate) thread_starting_size: usize,
+    pub(crate) max_size: usize,
+    /// Whether `max_size` was set explicitly, in which
+    /// case it also limits the global buffer.
+    pub(crate) limit_global: bool,
+    pub(crate) max_total_size: Option<usize>,
+    pub(crate) idle_timeout: Option<Duration>,
+    pub(crate) storage: Storage,
+    pub(crate) return_policy: ReturnPolicy,
+    pub(crate) shard_key: Option<Box<ShardKey>>,
+    pub(crate) supplier: Option<Box<TrySupplier<T>>>,
+    pub(crate) recycler: Option<Box<Recycler<T>>>,
+    pub(crate) validator: Option<Box<Validator<T>>>,
+    pub(crate) create_hook: Option<Box<Hook<T>>>,
+    pub(crate) checkout_hook: Option<Box<Hook<T>>>,
+    pub(crate) return_hook: Option<Box<Hook<T>>>,
+    pub(crate) drop_hook: Option<Box<Hook<T>>>,
 }
 
 impl<T> PoolBuilder<T>
@@ -34,57 +77,379 @@ where
         self
     }
 
+    /// Sets the number of objects each thread's
+    /// buffer is filled with when the thread first
+    /// retrieves or returns an object.
+    ///
+    /// This is equivalent to each thread calling
+    /// `Pool::prefill_local` before using the pool.
+    /// Other operations, such as `Pool::clear` or
+    /// `Pool::local_size`, don't fill the buffer.
+    /// Defaults to 0.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::Arc;
+    /// use std::thread;
+    /// use swimmer::Pool;
+    ///
+    /// let pool: Arc<Pool<String>> = Arc::new(
+    ///     swimmer::builder()
+    ///         .with_thread_starting_size(8)
+    ///         .build(),
+    /// );
+    ///
+    /// let other = Arc::clone(&pool);
+    /// let size = thread::spawn(move || {
+    ///     drop(other.get());
+    ///     other.local_size()
+    /// })
+    /// .join()
+    /// .unwrap();
+    /// assert_eq!(size, 8);
+    /// ```
+    pub fn with_thread_starting_size(mut self, thread_starting_size: usize) -> Self {
+        self.thread_starting_size = thread_starting_size;
+        self
+    }
+
+    /// Sets the maximum number of objects
+    /// a single thread's buffer may hold.
+    ///
+    /// When an object is returned to a full thread-local
+    /// buffer, the buffer's oldest objects are moved into
+    /// the global buffer, where other threads can retrieve
+    /// them. Once set, this also limits the global buffer:
+    /// threads move at most `max_size` objects into it, and
+    /// objects returned once both buffers are full are dropped.
+    /// This includes objects returned under `ReturnPolicy::Global`.
+    /// Starting objects and objects added using `Pool::extend` or
+    /// `Pool::reserve` don't count towards this limit.
+    ///
+    /// Defaults to 64, without limiting the global buffer, so
+    /// that a pool keeps every returned object unless a limit
+    /// is set. To bound the number of objects kept by the pool
+    /// as a whole, use `with_max_total_size`.
+    pub fn with_max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size;
+        self.limit_global = true;
+        self
+    }
+
+    /// Returns how many objects threads may
+    /// move into the global buffer.
+    pub(crate) fn global_limit(&self) -> usize {
+        if self.limit_global {
+            self.max_size
+        } else {
+            usize::MAX
+        }
+    }
+
+    /// Sets the maximum number of objects the
+    /// pool may hold across all threads.
+    ///
+    /// Objects returned to a full pool are dropped.
+    /// By default, the pool is unbounded.
+    pub fn with_max_total_size(mut self, max_total_size: usize) -> Self {
+        self.max_total_size = Some(max_total_size);
+        self
+    }
+
+    /// Sets how long objects may stay in the pool
+    /// without being used before they are dropped.
+    ///
+    /// Expired objects are evicted lazily when a thread
+    /// retrieves or returns an object, or explicitly
+    /// using `Pool::evict_idle`. By default, objects
+    /// are kept indefinitely.
+    pub fn with_idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        self.idle_timeout = Some(idle_timeout);
+        self
+    }
+
+    /// Sets the strategy used to store objects
+    /// which aren't checked out.
+    ///
+    /// Defaults to `Storage::ThreadLocal`. With any other
+    /// strategy, threads don't have their own buffers, so
+    /// `with_max_size` has no effect.
+    ///
+    /// # Examples
+    /// ```
+    /// use swimmer::{Pool, Storage};
+    /// let pool: Pool<String> = swimmer::builder()
+    ///     .with_storage(Storage::Global)
+    ///     .build();
+    ///
+    /// drop(pool.get());
+    /// assert_eq!(pool.local_size(), 0);
+    /// assert_eq!(pool.global_size(), 1);
+    /// ```
+    pub fn with_storage(mut self, storage: Storage) -> Self {
+        self.storage = storage;
+        self
+    }
+
+    /// Sets where objects are returned to when they are
+    /// dropped on a different thread than the one which
+    /// retrieved them.
+    ///
+    /// Defaults to `ReturnPolicy::Current`. This only
+    /// applies to pools using `Storage::ThreadLocal`.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::thread;
+    /// use swimmer::{Pool, ReturnPolicy};
+    /// let pool: Pool<Vec<u8>> = swimmer::builder()
+    ///     .with_return_policy(ReturnPolicy::Origin)
+    ///     .build();
+    ///
+    /// let buffer = pool.get();
+    /// thread::scope(|s| {
+    ///     s.spawn(move || drop(buffer));
+    /// });
+    ///
+    /// // The buffer was returned to this thread
+    /// assert_eq!(pool.global_size(), 0);
+    /// drop(pool.get());
+    /// assert_eq!(pool.local_size(), 1);
+    /// ```
+    pub fn with_return_policy(mut self, return_policy: ReturnPolicy) -> Self {
+        self.return_policy = return_policy;
+        self
+    }
+
+    /// Uses the given closure to choose the shard used
+    /// by the calling thread, rather than a hash of its ID.
+    ///
+    /// The shard is the returned key modulo the number of
+    /// shards. This only applies to pools using `Storage::Sharded`.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::cell::Cell;
+    /// use swimmer::{Pool, Storage};
+    ///
+    /// thread_local! {
+    ///     // Set by each worker thread of a runtime
+    ///     static WORKER_INDEX: Cell<usize> = Cell::new(0);
+    /// }
+    ///
+    /// let pool: Pool<Vec<u8>> = swimmer::builder()
+    ///     .with_storage(Storage::Sharded(4))
+    ///     .with_shard_key(|| WORKER_INDEX.with(Cell::get))
+    ///     .build();
+    /// ```
+    pub fn with_shard_key<K>(mut self, key: K) -> Self
+    where
+        K: Fn() -> usize + Send + Sync + 'static,
+    {
+        self.shard_key = Some(Box::new(key));
+        self
+    }
+
     /// Uses the given closure for initializing
     /// new objects in the pool.
     pub fn with_supplier<S>(mut self, supplier: S) -> Self
     where
         S: Fn() -> T + Send + Sync + 'static,
     {
-        self.supplier = Some(Box::new(supplier));
+        self.supplier = Some(Box::new(move || Ok(supplier())));
         self
     }
 
-    /// Builds a pool using the configured settings.
-    pub fn build(self) -> Pool<T> {
-        let values = CachedThreadLocal::new();
+    /// Uses the given fallible closure for
+    /// initializing new objects in the pool.
+    ///
+    /// Errors returned by the closure are passed on
+    /// by `Pool::try_get` and `PoolBuilder::try_build`;
+    /// infallible functions such as `Pool::get` panic instead.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::fs::File;
+    /// use std::io;
+    /// use swimmer::Pool;
+    ///
+    /// let pool: Pool<Vec<File>> = swimmer::builder()
+    ///     .with_try_supplier(|| -> io::Result<Vec<File>> {
+    ///         Ok(vec![File::open("does/not/exist")?])
+    ///     })
+    ///     .build();
+    ///
+    /// assert!(pool.try_get().is_err());
+    /// ```
+    pub fn with_try_supplier<S, E>(mut self, supplier: S) -> Self
+    where
+        S: Fn() -> Result<T, E> + Send + Sync + 'static,
+        E: Into<CreateError>,
+    {
+        self.supplier = Some(Box::new(move || supplier().map_err(Into::into)));
+        self
+    }
 
-        for _ in 0..self.starting_size {
-            if let Some(supplier) = self.supplier.as_ref() {
-                values.get_or(|| init()).borrow_mut().push(supplier())
-            } else {
-                values.get_or(|| init()).borrow_mut().push(T::new())
-            }
-        }
+    /// Uses the given closure for recycling objects
+    /// returned to the pool, instead of `Recyclable::recycle`.
+    pub fn with_recycler<R>(mut self, recycler: R) -> Self
+    where
+        R: Fn(&mut T) + Send + Sync + 'static,
+    {
+        self.recycler = Some(Box::new(recycler));
+        self
+    }
 
-        Pool {
-            values,
-            settings: self,
-        }
+    /// Uses the given closure for validating objects
+    /// returned to the pool.
+    ///
+    /// The closure is called after the object has
+    /// been recycled. If it returns `false`, the
+    /// object is dropped instead of being returned
+    /// to the pool. See `Pool::rejected`.
+    pub fn with_validator<V>(mut self, validator: V) -> Self
+    where
+        V: Fn(&T) -> bool + Send + Sync + 'static,
+    {
+        self.validator = Some(Box::new(validator));
+        self
+    }
+
+    /// Calls the given closure on every newly
+    /// initialized object, including the starting objects.
+    pub fn with_create_hook<H>(mut self, hook: H) -> Self
+    where
+        H: Fn(&mut T) + Send + Sync + 'static,
+    {
+        self.create_hook = Some(Box::new(hook));
+        self
+    }
+
+    /// Calls the given closure on every object
+    /// retrieved from the pool, right before it is
+    /// handed to the caller.
+    pub fn with_checkout_hook<H>(mut self, hook: H) -> Self
+    where
+        H: Fn(&mut T) + Send + Sync + 'static,
+    {
+        self.checkout_hook = Some(Box::new(hook));
+        self
+    }
+
+    /// Calls the given closure on every object
+    /// returned to the pool, before it is recycled.
+    ///
+    /// # Examples
+    /// ```
+    /// use swimmer::Pool;
+    /// let pool: Pool<Vec<u8>> = swimmer::builder()
+    ///     .with_return_hook(|secret: &mut Vec<u8>| {
+    ///         // Overwrite the contents before they are cleared
+    ///         for byte in secret.iter_mut() {
+    ///             *byte = 0;
+    ///         }
+    ///     })
+    ///     .build();
+    ///
+    /// let mut secret = pool.get();
+    /// secret.extend_from_slice(b"password");
+    /// ```
+    pub fn with_return_hook<H>(mut self, hook: H) -> Self
+    where
+        H: Fn(&mut T) + Send + Sync + 'static,
+    {
+        self.return_hook = Some(Box::new(hook));
+        self
+    }
+
+    /// Calls the given closure on every object which
+    /// is dropped by the pool rather than handed out
+    /// again: objects which fail validation, are returned
+    /// to a full pool, are evicted or shrunk away, or
+    /// are still in the pool when it is dropped.
+    pub fn with_drop_hook<H>(mut self, hook: H) -> Self
+    where
+        H: Fn(&mut T) + Send + Sync + 'static,
+    {
+        self.drop_hook = Some(Box::new(hook));
+        self
+    }
+
+    /// Builds a pool using the configured settings.
+    ///
+    /// The starting objects are placed into the
+    /// pool's global buffer, so they are available
+    /// to every thread.
+    ///
+    /// # Panics
+    /// Panics if initializing a starting object fails.
+    /// See `try_build`.
+    pub fn build(self) -> Pool<T> {
+        self.build_with(vec![])
     }
 
     /// Builds a pool using the configured settings, and fill it with the given items.
+    ///
+    /// # Panics
+    /// Panics if initializing a starting object fails.
+    /// See `try_build_with`.
     pub fn build_with(self, items: Vec<T>) -> Pool<T> {
-        let values = CachedThreadLocal::new();
+        self.try_build_with(items).expect(CREATE_FAILED)
+    }
 
-        let size = items.len();
-        for itm in items {
-            values.get_or(|| init()).borrow_mut().push(itm);
-        }
+    /// Builds a pool using the configured settings,
+    /// returning an error if initializing a starting object fails.
+    ///
+    /// # Examples
+    /// ```
+    /// let result = swimmer::builder::<String>()
+    ///     .with_starting_size(4)
+    ///     .with_try_supplier(|| Err("no more strings"))
+    ///     .try_build();
+    ///
+    /// assert!(result.is_err());
+    /// ```
+    pub fn try_build(self) -> Result<Pool<T>, CreateError> {
+        self.try_build_with(vec![])
+    }
 
-        if size < self.starting_size {
-            let remainder = self.starting_size - size;
+    /// Builds a pool using the configured settings, and fill it with the given items,
+    /// returning an error if initializing a starting object fails.
+    pub fn try_build_with(self, mut items: Vec<T>) -> Result<Pool<T>, CreateError> {
+        if items.len() < self.starting_size {
+            let remainder = self.starting_size - items.len();
             for _ in 0..remainder {
-                if let Some(supplier) = self.supplier.as_ref() {
-                    values.get_or(|| init()).borrow_mut().push(supplier())
-                } else {
-                    values.get_or(|| init()).borrow_mut().push(T::new())
-                }
+                items.push(self.try_create()?);
             }
         }
 
-        Pool {
-            values,
-            settings: self,
+        Ok(Pool::from_parts(self, items))
+    }
+
+    pub(crate) fn try_create(&self) -> Result<T, CreateError> {
+        let mut value = if let Some(supplier) = self.supplier.as_ref() {
+            supplier()?
+        } else {
+            T::try_new()?
+        };
+
+        run_hook(&self.create_hook, &mut value);
+        Ok(value)
+    }
+
+    pub(crate) fn recycle(&self, value: &mut T) {
+        if let Some(recycler) = self.recycler.as_ref() {
+            recycler(value)
+        } else {
+            value.recycle()
+        }
+    }
+
+    pub(crate) fn validate(&self, value: &T) -> bool {
+        if let Some(validator) = self.validator.as_ref() {
+            validator(value)
+        } else {
+            true
         }
     }
 }
@@ -96,7 +461,27 @@ where
     fn default() -> Self {
         Self {
             starting_size: 0,
+            thread_starting_size: 0,
+            max_size: DEFAULT_MAX_SIZE,
+            limit_global: false,
+            max_total_size: None,
+            idle_timeout: None,
+            storage: Storage::default(),
+            return_policy: ReturnPolicy::default(),
+            shard_key: None,
             supplier: None,
+            recycler: None,
+            validator: None,
+            create_hook: None,
+            checkout_hook: None,
+            return_hook: None,
+            drop_hook: None,
         }
     }
 }
+
+pub(crate) fn run_hook<T>(hook: &Option<Box<Hook<T>>>, value: &mut T) {
+    if let Some(hook) = hook.as_ref() {
+        hook(value)
+    }
+}
@@ -0,0 +1,186 @@
+use crate::shared::Node;
+use crate::Pool;
+use std::fmt::{self, Debug, Formatter};
+use std::marker::PhantomData;
+use std::ops::{Deref, RangeBounds};
+use std::ptr::NonNull;
+use std::slice;
+
+/// An immutable, cheaply clonable view into a pooled
+/// byte buffer, similar to `Bytes` from the `bytes` crate.
+///
+/// Views can be cloned and split into smaller views
+/// without copying. The buffer is returned to its pool
+/// once every view into it has been dropped.
+///
+/// Objects of this type are obtained using `Recycled::freeze`
+/// or `OwnedRecycled::freeze`.
+///
+/// # Examples
+/// ```
+/// use swimmer::{Pool, Recycled};
+/// let pool: Pool<Vec<u8>> = Pool::new();
+///
+/// let mut buf = pool.get();
+/// buf.extend_from_slice(b"header:body");
+/// let mut body = Recycled::freeze(buf);
+///
+/// let header = body.split_to(7);
+/// assert_eq!(&header[..], b"header:");
+/// assert_eq!(&body[..], b"body");
+///
+/// drop(header);
+/// assert_eq!(pool.size(), 0);
+/// drop(body);
+/// assert_eq!(pool.size(), 1);
+/// ```
+pub struct FrozenRecycled<'a> {
+    node: NonNull<Node<Vec<u8>>>,
+    /// The viewed part of the buffer, which
+    /// can't move while the node is referenced.
+    ptr: *const u8,
+    len: usize,
+    _marker: PhantomData<&'a Pool<Vec<u8>>>,
+}
+
+impl<'a> FrozenRecycled<'a> {
+    /// Creates a view of the whole buffer held by `node`,
+    /// taking over the caller's reference to it.
+    ///
+    /// The node's pool must live for `'a`.
+    pub(crate) fn new(node: NonNull<Node<Vec<u8>>>) -> Self {
+        let buf = unsafe {
+            // Safe because the caller holds a reference.
+            Node::value(node)
+        };
+
+        FrozenRecycled {
+            node,
+            ptr: buf.as_ptr(),
+            len: buf.len(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns a view of the given range
+    /// of this view, without copying.
+    ///
+    /// # Panics
+    /// Panics if the range is out of bounds.
+    ///
+    /// # Examples
+    /// ```
+    /// use swimmer::{Pool, Recycled};
+    /// let pool: Pool<Vec<u8>> = Pool::new();
+    ///
+    /// let mut buf = pool.get();
+    /// buf.extend_from_slice(b"hello world");
+    /// let buf = Recycled::freeze(buf);
+    ///
+    /// assert_eq!(&buf.slice(6..)[..], b"world");
+    /// assert_eq!(&buf.slice(..=4)[..], b"hello");
+    /// ```
+    pub fn slice<R>(&self, range: R) -> Self
+    where
+        R: RangeBounds<usize>,
+    {
+        let bytes = &self[(range.start_bound().cloned(), range.end_bound().cloned())];
+        let mut view = self.clone();
+        view.ptr = bytes.as_ptr();
+        view.len = bytes.len();
+        view
+    }
+
+    /// Splits the view in two at `at`, returning
+    /// the bytes before it and keeping those after it.
+    ///
+    /// # Panics
+    /// Panics if `at` is larger than the view's length.
+    pub fn split_to(&mut self, at: usize) -> Self {
+        let front = self.slice(..at);
+        *self = self.slice(at..);
+        front
+    }
+
+    /// Splits the view in two at `at`, returning
+    /// the bytes after it and keeping those before it.
+    ///
+    /// # Panics
+    /// Panics if `at` is larger than the view's length.
+    pub fn split_off(&mut self, at: usize) -> Self {
+        let back = self.slice(at..);
+        *self = self.slice(..at);
+        back
+    }
+}
+
+// Safe because the buffer is shared between threads
+// just like the value of an `Arc<Vec<u8>>`.
+unsafe impl<'a> Send for FrozenRecycled<'a> {}
+
+unsafe impl<'a> Sync for FrozenRecycled<'a> {}
+
+impl<'a> Clone for FrozenRecycled<'a> {
+    fn clone(&self) -> Self {
+        unsafe {
+            // Safe because `self` holds a reference.
+            Node::acquire(self.node);
+        }
+
+        FrozenRecycled {
+            node: self.node,
+            ptr: self.ptr,
+            len: self.len,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a> Drop for FrozenRecycled<'a> {
+    fn drop(&mut self) {
+        unsafe {
+            // Safe because `self` holds a reference
+            // and is never used again, and the pool
+            // lives for `'a`.
+            Node::release(self.node);
+        }
+    }
+}
+
+impl<'a> AsRef<[u8]> for FrozenRecycled<'a> {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl<'a> Deref for FrozenRecycled<'a> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        unsafe {
+            // Safe because the buffer can't be modified
+            // or moved while `self` holds a reference.
+            slice::from_raw_parts(self.ptr, self.len)
+        }
+    }
+}
+
+impl<'a> Debug for FrozenRecycled<'a> {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{:?}", &**self)
+    }
+}
+
+impl<'a> PartialEq for FrozenRecycled<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl<'a> Eq for FrozenRecycled<'a> {}
+
+impl<'a> PartialEq<[u8]> for FrozenRecycled<'a> {
+    fn eq(&self, other: &[u8]) -> bool {
+        **self == *other
+    }
+}
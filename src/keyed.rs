@@ -0,0 +1,143 @@
+use crate::{CreateError, Pool, PoolBuilder, Recyclable, Recycled};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{PoisonError, RwLock};
+
+/// A function which configures the pool
+/// used for objects with a given key.
+pub type PoolFactory<K, T> = dyn Fn(&K) -> PoolBuilder<T> + Send + Sync;
+
+/// A collection of pools, each holding
+/// the objects for one key.
+///
+/// This is useful when objects differ by some
+/// property which the caller cares about, such as
+/// the capacity of a buffer: by using a size class as
+/// the key, a request for a small buffer never receives
+/// a huge one.
+///
+/// The pool for a key is created the first time
+/// an object with that key is requested, using the
+/// builder returned by the function passed to `new`.
+/// This allows each pool to have its own supplier.
+///
+/// # Examples
+/// ```
+/// use swimmer::KeyedPool;
+///
+/// let pool: KeyedPool<usize, Vec<u8>> = KeyedPool::new(|&capacity: &usize| {
+///     swimmer::builder().with_supplier(move || Vec::with_capacity(capacity))
+/// });
+///
+/// let small = pool.get(&16);
+/// let large = pool.get(&4096);
+/// assert!(small.capacity() >= 16 && small.capacity() < 4096);
+/// assert!(large.capacity() >= 4096);
+/// ```
+pub struct KeyedPool<K, T>
+where
+    T: Recyclable,
+{
+    /// The pools are boxed so that they keep their
+    /// address when the map grows; see `pool`.
+    pools: RwLock<HashMap<K, Box<Pool<T>>>>,
+    factory: Box<PoolFactory<K, T>>,
+}
+
+impl<K, T> KeyedPool<K, T>
+where
+    K: Eq + Hash + Clone,
+    T: Recyclable,
+{
+    /// Creates a new keyed pool, which uses `factory`
+    /// to configure the pool for each key.
+    pub fn new<F>(factory: F) -> KeyedPool<K, T>
+    where
+        F: Fn(&K) -> PoolBuilder<T> + Send + Sync + 'static,
+    {
+        KeyedPool {
+            pools: RwLock::new(HashMap::new()),
+            factory: Box::new(factory),
+        }
+    }
+
+    /// Retrieves a value from the pool for `key`.
+    ///
+    /// The value is returned to the same pool once dropped.
+    pub fn get(&self, key: &K) -> Recycled<'_, T> {
+        self.pool(key).get()
+    }
+
+    /// Retrieves a value from the pool for `key`, returning
+    /// an error if initializing a new object fails.
+    ///
+    /// See `Pool::try_get`.
+    pub fn try_get(&self, key: &K) -> Result<Recycled<'_, T>, CreateError> {
+        self.pool(key).try_get()
+    }
+
+    /// Returns the pool for `key`, creating it
+    /// if it doesn't exist yet.
+    ///
+    /// # Panics
+    /// Panics if the pool has to be created and
+    /// building it fails, like `PoolBuilder::build`.
+    ///
+    /// # Examples
+    /// ```
+    /// use swimmer::KeyedPool;
+    /// let pool: KeyedPool<&str, String> =
+    ///     KeyedPool::new(|_: &&str| swimmer::builder().with_starting_size(2));
+    ///
+    /// assert_eq!(pool.pool(&"names").size(), 2);
+    /// ```
+    pub fn pool(&self, key: &K) -> &Pool<T> {
+        let pools = self.pools.read().unwrap_or_else(PoisonError::into_inner);
+        let pool = match pools.get(key) {
+            Some(pool) => &**pool as *const Pool<T>,
+            None => {
+                drop(pools);
+                self.insert(key)
+            }
+        };
+
+        unsafe {
+            // Safe because pools are boxed and are never
+            // removed from the map while `self` is borrowed,
+            // so the pool lives as long as `self`.
+            &*pool
+        }
+    }
+
+    /// Returns the total number of objects
+    /// held by the pools for all keys.
+    pub fn size(&self) -> usize {
+        let pools = self.pools.read().unwrap_or_else(PoisonError::into_inner);
+        pools.values().map(|pool| pool.size()).sum()
+    }
+
+    /// Returns the number of keys
+    /// which have a pool.
+    pub fn len(&self) -> usize {
+        let pools = self.pools.read().unwrap_or_else(PoisonError::into_inner);
+        pools.len()
+    }
+
+    /// Returns whether no pools have been created.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Creates the pool for `key` unless another
+    /// thread already did, returning a pointer to it.
+    #[cold]
+    fn insert(&self, key: &K) -> *const Pool<T> {
+        // Build the pool without holding the lock,
+        // since building may initialize objects.
+        let pool = Box::new((self.factory)(key).build());
+
+        let mut pools = self.pools.write().unwrap_or_else(PoisonError::into_inner);
+        let pool = pools.entry(key.clone()).or_insert(pool);
+        &**pool as *const Pool<T>
+    }
+}
@@ -54,6 +54,29 @@
 //! by benchmarks to be more than twice as performant as using
 //! a locked `Vec` or `crossbeam::SegQueue`.
 //!
+//! In addition to the thread-local buffers, the pool has a
+//! global buffer shared between all threads. When a thread-local
+//! buffer grows too large, half of its objects are moved into the
+//! global buffer; when a thread-local buffer runs out of objects,
+//! it is refilled from the global buffer before any new objects are
+//! initialized. This prevents objects from piling up in threads
+//! which return more objects than they retrieve, such as
+//! the consumer in a producer/consumer pipeline. If a maximum
+//! size is set using `PoolBuilder::with_max_size()`, the global
+//! buffer holds at most that many objects moved there by threads,
+//! like the thread-local buffers; any further objects are dropped.
+//!
+//! When a thread exits, the objects held by its buffer are
+//! moved into the global buffer as far as it has room, so they
+//! can still be reused. The buffer itself, along with any objects
+//! which didn't fit, is kept until the pool is dropped, or until
+//! it is reused by a new thread. Still, a pool used by
+//! many short-lived threads may perform better with a different
+//! [`Storage`](enum.Storage.html)
+//! strategy, such as a single lock-free queue shared by all
+//! threads or a fixed number of shards. The strategy is set
+//! using `PoolBuilder::with_storage()`.
+//!
 //! # Supplier
 //! In some cases, you may want to specify your own function
 //! for initializing new objects rather than use the default
@@ -76,16 +99,73 @@
 //! assert_eq!(vec.capacity(), 128);
 //! ```
 //!
+//! If initializing an object can fail, use
+//! `PoolBuilder::with_try_supplier()` instead, or override
+//! `Recyclable::try_new()`. Errors are then returned by
+//! `Pool::try_get()` and `PoolBuilder::try_build()`, while
+//! `Pool::get()` and `PoolBuilder::build()` panic.
+//!
 //! Note, however, that the supplier function is only
 //! called when the object is first initialized: it is
-//! not used to recycle the object. This means that there
-//! is currently no way to implement custom recycling
-//! functionality.
+//! not used to recycle the object. See the next section
+//! for customizing recycling.
+//!
+//! # Recycler
+//! Similarly, you can use `PoolBuilder::with_recycler()`
+//! to replace `Recyclable::recycle()` with your own
+//! closure. The closure is called on every object
+//! returned to the pool.
+//!
+//! For example, you may want to limit the amount of
+//! memory held by pooled vectors:
+//! ```
+//! use swimmer::Pool;
+//! let pool: Pool<Vec<u8>> = swimmer::builder()
+//!     .with_recycler(|vec: &mut Vec<u8>| {
+//!         vec.clear();
+//!         vec.shrink_to(1024);
+//!     })
+//!     .build();
+//!
+//! let mut vec = pool.get();
+//! vec.resize(4096, 0);
+//! drop(vec);
+//!
+//! let vec = pool.get();
+//! assert!(vec.is_empty());
+//! assert!(vec.capacity() <= 1024);
+//! ```
+//!
+//! # Bounded pools
+//! A `Pool` never makes callers wait: if there are no objects
+//! left in the pool, a new one is initialized. For expensive
+//! objects, such as database clients, you may instead want to
+//! limit the number of objects in existence. In that case, you can
+//! use a [`BoundedPool`](struct.BoundedPool.html), which allows
+//! at most a fixed number of objects to be checked out at once.
+//! Retrieving an object from an exhausted `BoundedPool` waits until
+//! another object is returned, either by blocking the current thread
+//! or asynchronously using `BoundedPool::get_async()`.
+//!
+//! # Keyed pools
+//! A single pool hands out its objects regardless of their
+//! state, so a pool of buffers may return a huge buffer for
+//! a request which only needs a few bytes. A
+//! [`KeyedPool`](struct.KeyedPool.html) solves this by keeping
+//! a separate pool for each key, such as a buffer size class,
+//! with each pool configured based on its key.
+//!
+//! For byte buffers in particular, a [`BufPool`](struct.BufPool.html)
+//! sorts buffers into buckets by their capacity, and returns a buffer
+//! with at least the requested capacity.
 //!
 //! # Crate features
 //! * `hashbrown-impls`: implements `Recyclable` for `hashbrown::HashMap` and
 //! `hashbrown::HashSet`.
 //! * `smallvec-impls`: implements `Recyclable` for `SmallVec`.
+//! * `stats`: keeps track of pool statistics, which can be
+//!   retrieved using `Pool::stats()`. Maintaining the counters
+//!   adds some overhead to retrieving and returning objects.
 //!
 //! # Examples
 //! Basic usage:
@@ -177,35 +257,251 @@
 //!
 //! let value = POOL.get();
 //! ```
+//!
+//! Moving a pooled object into another thread, using
+//! an `Arc<Pool>` so that the object doesn't borrow the pool:
+//! ```
+//! use std::sync::Arc;
+//! use std::thread;
+//! use swimmer::Pool;
+//!
+//! let pool: Arc<Pool<Vec<u8>>> = Arc::new(Pool::new());
+//!
+//! let mut buf = pool.get_owned();
+//! let handle = thread::spawn(move || {
+//!     buf.extend_from_slice(b"hello");
+//!     buf.len()
+//! });
+//!
+//! assert_eq!(handle.join().unwrap(), 5);
+//! ```
 
+mod bounded;
+mod buf;
 mod builder;
+mod frozen;
+mod keyed;
+mod mapped;
+mod owned;
+mod reclaim;
 #[allow(clippy::implicit_hasher)] // No way to initialize a hash map with generic hasher
 mod recyclable;
+mod shared;
+mod stats;
+mod storage;
 
-pub use builder::{builder, PoolBuilder, Supplier};
-pub use recyclable::Recyclable;
+pub use bounded::{BoundedPool, BoundedRecycled, Checkout};
+pub use buf::{BufPool, BufRecycled};
+pub use builder::{
+    builder, Hook, PoolBuilder, Recycler, ShardKey, Supplier, TrySupplier, Validator,
+};
+pub use frozen::FrozenRecycled;
+pub use keyed::{KeyedPool, PoolFactory};
+pub use mapped::MappedRecycled;
+pub use owned::OwnedRecycled;
+pub use recyclable::{CreateError, Recyclable};
+pub use shared::SharedRecycled;
+#[cfg(feature = "stats")]
+pub use stats::PoolStats;
+pub use storage::{ReturnPolicy, Storage};
 
-use std::cell::RefCell;
+use reclaim::Alive;
+use shared::Node;
+use stats::{Counters, Event, PeakSize};
+use std::cell::{Cell, RefCell};
 use std::cmp::Ordering;
 use std::fmt::{self, Debug, Display, Formatter};
-use std::mem::ManuallyDrop;
-use std::mem::MaybeUninit;
+use std::mem::{self, ManuallyDrop};
 use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering as AtomicOrdering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread::{self, ThreadId};
+use std::time::{Duration, Instant};
+use storage::{take_expired, Shared, Store};
 use thread_local::CachedThreadLocal;
 
 /// A thread-safe object pool, used
 /// to reuse objects without reallocating.
 ///
 /// See the crate-level documentation for more information.
-#[derive(Default)]
 pub struct Pool<T>
 where
     T: Recyclable,
 {
     settings: PoolBuilder<T>,
-    values: CachedThreadLocal<RefCell<Vec<T>>>,
+    values: CachedThreadLocal<Local<T>>,
+    /// Information about each thread's buffer,
+    /// readable from any thread.
+    locals: Mutex<Vec<Arc<LocalInfo>>>,
+    /// Boxed so that exit hooks can refer to it.
+    global: Box<Shared<T>>,
+    /// The number of objects stored in all buffers.
+    total: AtomicUsize,
+    /// The number of objects rejected by the validator.
+    rejected: AtomicUsize,
+    peak_size: PeakSize,
+    alive: Alive,
+}
+
+impl<T> Default for Pool<T>
+where
+    T: Recyclable,
+{
+    fn default() -> Self {
+        Pool::new()
+    }
+}
+
+/// An object stored in one of the pool's buffers.
+struct Idle<T> {
+    value: T,
+    /// When the object was returned to the pool.
+    /// Only recorded if the pool has an idle timeout.
+    since: Option<Instant>,
+}
+
+impl<T> Idle<T> {
+    fn is_expired(&self, timeout: Duration, now: Instant) -> bool {
+        match self.since {
+            Some(since) => now.duration_since(since) >= timeout,
+            None => false,
+        }
+    }
+}
+
+impl<T> Drop for Pool<T>
+where
+    T: Recyclable,
+{
+    fn drop(&mut self) {
+        // Wait for running exit hooks, and
+        // prevent new ones from accessing the pool.
+        *self.alive.lock().unwrap_or_else(PoisonError::into_inner) = false;
+
+        // Run the drop hook on the objects still in the pool.
+        let hook = match self.settings.drop_hook.as_ref() {
+            Some(hook) => hook,
+            None => return,
+        };
+
+        let mut global = self.global.take_all();
+        for local in self.values.iter_mut() {
+            global.append(&mut local.remote.take());
+        }
+        let locals = self
+            .values
+            .iter_mut()
+            .flat_map(|local| local.values.get_mut().iter_mut());
+
+        for idle in global.iter_mut().chain(locals) {
+            hook(&mut idle.value);
+        }
+    }
+}
+
+/// A thread's buffer of objects.
+struct Local<T>
+where
+    T: Recyclable,
+{
+    values: RefCell<Vec<Idle<T>>>,
+    remote: Arc<Remote<T>>,
+    /// Empty nodes for shared objects.
+    nodes: RefCell<Vec<Box<Node<T>>>>,
+    /// Whether the buffer still has to be filled up
+    /// to the thread starting size. This is deferred
+    /// until the thread retrieves or returns an object.
+    warm_up: Cell<bool>,
+    info: Arc<LocalInfo>,
+}
+
+/// The list which an object is returned to
+/// under `ReturnPolicy::Origin`, if any.
+type Origin<T> = Option<Arc<Remote<T>>>;
+
+/// Objects returned to a thread by other threads.
+/// See `ReturnPolicy::Origin`.
+struct Remote<T> {
+    values: Mutex<Vec<Idle<T>>>,
+    info: Arc<LocalInfo>,
+}
+
+impl<T> Remote<T> {
+    fn push(&self, value: Idle<T>) {
+        let mut values = self.lock();
+        values.push(value);
+        self.info
+            .returned
+            .store(values.len(), AtomicOrdering::Relaxed);
+    }
+
+    fn take(&self) -> Vec<Idle<T>> {
+        if self.info.returned.load(AtomicOrdering::Relaxed) == 0 {
+            return vec![];
+        }
+
+        let mut values = self.lock();
+        self.info.returned.store(0, AtomicOrdering::Relaxed);
+        mem::take(&mut *values)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<Idle<T>>> {
+        self.values.lock().unwrap_or_else(PoisonError::into_inner)
+    }
 }
 
+impl<T> Local<T>
+where
+    T: Recyclable,
+{
+    /// Publishes the size of `values` to other threads.
+    fn sync(&self, values: &[Idle<T>]) {
+        self.info.size.store(values.len(), AtomicOrdering::Relaxed);
+    }
+
+    /// Moves the objects returned to this buffer by other
+    /// threads into `values`, the borrowed buffer.
+    fn collect_returned(&self, values: &mut Vec<Idle<T>>) {
+        let mut returned = self.remote.take();
+        if !returned.is_empty() {
+            values.append(&mut returned);
+            self.sync(values);
+        }
+    }
+}
+
+/// The parts of a thread's buffer which
+/// can be accessed by other threads.
+struct LocalInfo {
+    thread: ThreadId,
+    size: AtomicUsize,
+    /// The number of objects in the buffer's `Remote`
+    /// list, readable without locking.
+    returned: AtomicUsize,
+    /// The size which the owning thread should shrink
+    /// its buffer to the next time it uses the pool,
+    /// or `NO_TRIM`. Set by `Pool::shrink_to`.
+    trim_to: AtomicUsize,
+    /// Whether the thread currently using the buffer
+    /// has registered an exit hook, which moves the
+    /// buffer's objects to the global buffer once
+    /// the thread exits.
+    has_exit_hook: AtomicBool,
+    counters: Counters,
+}
+
+impl LocalInfo {
+    /// Returns the number of objects held by the buffer,
+    /// including the ones returned by other threads.
+    fn len(&self) -> usize {
+        self.size.load(AtomicOrdering::Relaxed) + self.returned.load(AtomicOrdering::Relaxed)
+    }
+}
+
+/// The value of `LocalInfo::trim_to` when
+/// no shrinking has been requested.
+const NO_TRIM: usize = usize::MAX;
+
 impl<T> Pool<T>
 where
     T: Recyclable,
@@ -258,10 +554,95 @@ where
     pub fn get(&self) -> Recycled<T> {
         let value = self.get_raw_value();
 
-        Recycled {
-            value: ManuallyDrop::new(value),
-            pool: self,
-        }
+        Recycled::new(value, self)
+    }
+
+    /// Retrieves a value from the pool, returning an
+    /// error if a new object needs to be initialized
+    /// and initializing it fails.
+    ///
+    /// `get` panics in this case instead. Initialization
+    /// can only fail if the pool uses a fallible supplier
+    /// (see `PoolBuilder::with_try_supplier`) or if `T`
+    /// overrides `Recyclable::try_new`.
+    ///
+    /// # Examples
+    /// ```
+    /// use swimmer::Pool;
+    /// let pool: Pool<String> = swimmer::builder()
+    ///     .with_try_supplier(|| Err("no more strings"))
+    ///     .build();
+    ///
+    /// let error = pool.try_get().unwrap_err();
+    /// assert_eq!(error.to_string(), "no more strings");
+    ///
+    /// // Objects already in the pool can still be retrieved
+    /// drop(pool.attach(String::new()));
+    /// assert!(pool.try_get().is_ok());
+    /// ```
+    pub fn try_get(&self) -> Result<Recycled<'_, T>, CreateError> {
+        let value = self.try_get_raw_value()?;
+
+        Ok(Recycled::new(value, self))
+    }
+
+    /// Retrieves a value from a pool held in an `Arc`.
+    ///
+    /// This is equivalent to `get`, except for that
+    /// the returned smart pointer holds a reference
+    /// to the pool rather than borrowing it. As a result,
+    /// the value is `'static` (provided that `T` is) and
+    /// can be moved into other threads or stored in structs.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::Arc;
+    /// use swimmer::Pool;
+    /// let pool: Arc<Pool<String>> = Arc::new(Pool::with_size(1));
+    ///
+    /// let string = pool.get_owned();
+    /// assert_eq!(pool.size(), 0);
+    ///
+    /// drop(string);
+    /// assert_eq!(pool.size(), 1);
+    /// ```
+    pub fn get_owned(self: &Arc<Self>) -> OwnedRecycled<T> {
+        let value = self.get_raw_value();
+
+        OwnedRecycled::new(value, Arc::clone(self))
+    }
+
+    /// Retrieves a value from a pool held in an `Arc`,
+    /// returning an error if initializing a new object fails.
+    ///
+    /// See `get_owned` and `try_get`.
+    pub fn try_get_owned(self: &Arc<Self>) -> Result<OwnedRecycled<T>, CreateError> {
+        let value = self.try_get_raw_value()?;
+
+        Ok(OwnedRecycled::new(value, Arc::clone(self)))
+    }
+
+    /// Retrieves a value from a pool held in an `Arc`,
+    /// as a pointer which can be cloned cheaply to
+    /// share the value. The value is returned to the
+    /// pool once the last clone is dropped.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::Arc;
+    /// use swimmer::Pool;
+    /// let pool: Arc<Pool<String>> = Arc::new(Pool::with_size(1));
+    ///
+    /// let string = pool.get_shared();
+    /// let clone = string.clone();
+    /// drop(string);
+    /// assert_eq!(pool.size(), 0);
+    ///
+    /// drop(clone);
+    /// assert_eq!(pool.size(), 1);
+    /// ```
+    pub fn get_shared(self: &Arc<Self>) -> SharedRecycled<T> {
+        SharedRecycled::from(self.get_owned())
     }
 
     /// Returns the current size of the pool.
@@ -270,6 +651,9 @@ where
     /// the size is decremented; when it is returned, the
     /// size is incremented.
     ///
+    /// This includes the objects held by every thread's
+    /// buffer as well as by the global buffer.
+    ///
     /// # Examples
     /// ```
     /// use swimmer::Pool;
@@ -284,7 +668,221 @@ where
     /// assert_eq!(pool.size(), 16);
     /// ```
     pub fn size(&self) -> usize {
-        self.values.get_or(|| init()).borrow().len()
+        self.total.load(AtomicOrdering::Relaxed)
+    }
+
+    /// Returns the number of objects held by
+    /// the calling thread's buffer.
+    ///
+    /// This includes the objects which other threads
+    /// returned to this thread under `ReturnPolicy::Origin`.
+    ///
+    /// # Examples
+    /// ```
+    /// use swimmer::Pool;
+    /// let pool: Pool<String> = Pool::with_size(16);
+    ///
+    /// // Starting objects are placed into the global buffer
+    /// assert_eq!(pool.local_size(), 0);
+    /// assert_eq!(pool.global_size(), 16);
+    ///
+    /// // Retrieving an object moves a batch of objects
+    /// // from the global buffer to this thread's buffer
+    /// let _string = pool.get();
+    /// assert_eq!(pool.local_size() + pool.global_size(), 15);
+    /// ```
+    pub fn local_size(&self) -> usize {
+        self.local().info.len()
+    }
+
+    /// Returns the number of objects held by the
+    /// global buffer, which is shared between all threads.
+    ///
+    /// See `local_size` for an example.
+    pub fn global_size(&self) -> usize {
+        self.global.len()
+    }
+
+    /// Returns the number of objects held by each
+    /// thread's buffer, along with the ID of the thread.
+    /// Like `local_size`, this includes the objects
+    /// returned to each thread by other threads.
+    ///
+    /// Only threads which have used the pool are included.
+    /// The objects of threads which have exited are moved to
+    /// the global buffer as far as it has room. Such a
+    /// buffer may be reused by a new thread, in which
+    /// case it is still reported with the ID of the thread
+    /// which created it.
+    ///
+    /// The sizes are read without synchronizing with
+    /// the owning threads, so they may already be out
+    /// of date when this function returns.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::thread;
+    /// use swimmer::Pool;
+    /// let pool: Pool<String> = Pool::new();
+    ///
+    /// drop(pool.get());
+    /// let sizes = pool.thread_sizes();
+    ///
+    /// assert_eq!(sizes, vec![(thread::current().id(), 1)]);
+    /// ```
+    pub fn thread_sizes(&self) -> Vec<(ThreadId, usize)> {
+        self.locals()
+            .iter()
+            .map(|info| (info.thread, info.len()))
+            .collect()
+    }
+
+    /// Returns the maximum number of objects
+    /// a single thread's buffer may hold. If it was
+    /// set explicitly, threads may also move at most
+    /// this many objects into the global buffer.
+    ///
+    /// This only applies to pools using
+    /// `Storage::ThreadLocal`. See `PoolBuilder::with_max_size`.
+    ///
+    /// # Examples
+    /// ```
+    /// use swimmer::Pool;
+    /// let pool: Pool<String> = swimmer::builder()
+    ///     .with_max_size(16)
+    ///     .build();
+    ///
+    /// assert_eq!(pool.max_size(), 16);
+    /// ```
+    pub fn max_size(&self) -> usize {
+        self.settings.max_size
+    }
+
+    /// Returns the maximum number of objects
+    /// the whole pool may hold, or `None` if
+    /// the pool is unbounded.
+    ///
+    /// See `PoolBuilder::with_max_total_size`.
+    ///
+    /// # Examples
+    /// ```
+    /// use swimmer::Pool;
+    /// let pool: Pool<String> = swimmer::builder()
+    ///     .with_max_total_size(2)
+    ///     .build();
+    ///
+    /// assert_eq!(pool.max_total_size(), Some(2));
+    ///
+    /// let values = vec![pool.get(), pool.get(), pool.get()];
+    ///
+    /// // The third value is dropped instead
+    /// // of being returned to the pool
+    /// drop(values);
+    /// assert_eq!(pool.size(), 2);
+    /// ```
+    pub fn max_total_size(&self) -> Option<usize> {
+        self.settings.max_total_size
+    }
+
+    /// Returns how long objects may stay in the pool
+    /// without being used before they are dropped, or
+    /// `None` if objects are kept indefinitely.
+    ///
+    /// See `PoolBuilder::with_idle_timeout`.
+    pub fn idle_timeout(&self) -> Option<Duration> {
+        self.settings.idle_timeout
+    }
+
+    /// Returns the strategy used to store objects
+    /// which aren't checked out.
+    ///
+    /// See `PoolBuilder::with_storage`.
+    pub fn storage(&self) -> Storage {
+        self.settings.storage
+    }
+
+    /// Returns where objects are returned to when they
+    /// are dropped on a different thread than the one
+    /// which retrieved them.
+    ///
+    /// See `PoolBuilder::with_return_policy`.
+    pub fn return_policy(&self) -> ReturnPolicy {
+        self.settings.return_policy
+    }
+
+    /// Returns the number of objects which were
+    /// dropped instead of being returned to the pool
+    /// because they failed validation.
+    ///
+    /// See `PoolBuilder::with_validator`.
+    ///
+    /// # Examples
+    /// ```
+    /// use swimmer::Pool;
+    /// let pool: Pool<String> = swimmer::builder()
+    ///     .with_validator(|string: &String| string.capacity() <= 16)
+    ///     .build();
+    ///
+    /// let mut string = pool.get();
+    /// string.push_str("a string which is too long to keep");
+    /// drop(string);
+    ///
+    /// assert_eq!(pool.size(), 0);
+    /// assert_eq!(pool.rejected(), 1);
+    /// ```
+    pub fn rejected(&self) -> usize {
+        self.rejected.load(AtomicOrdering::Relaxed)
+    }
+
+    /// Returns a snapshot of the pool's statistics,
+    /// aggregated across all threads.
+    ///
+    /// This function is only available with the `stats` feature.
+    ///
+    /// # Examples
+    /// ```
+    /// use swimmer::Pool;
+    /// let pool: Pool<String> = Pool::with_size(1);
+    ///
+    /// let first = pool.get();
+    /// let second = pool.get();
+    ///
+    /// let stats = pool.stats();
+    /// assert_eq!(stats.gets, 2);
+    /// assert_eq!(stats.misses, 1);
+    /// assert_eq!(stats.checked_out, 2);
+    ///
+    /// drop(first);
+    /// drop(second);
+    ///
+    /// let stats = pool.stats();
+    /// assert_eq!(stats.returns, 2);
+    /// assert_eq!(stats.peak_size, 2);
+    /// assert_eq!(stats.checked_out, 0);
+    /// ```
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> PoolStats {
+        let mut stats = PoolStats {
+            rejected: self.rejected() as u64,
+            size: self.size(),
+            peak_size: self.peak_size.get(),
+            ..PoolStats::default()
+        };
+
+        for info in self.locals().iter() {
+            let counters = &info.counters;
+            stats.evicted += counters.count(Event::Evict);
+            stats.gets += counters.count(Event::Get);
+            stats.misses += counters.count(Event::Miss);
+            stats.returns += counters.count(Event::Return);
+            stats.attaches += counters.count(Event::Attach);
+            stats.detaches += counters.count(Event::Detach);
+            stats.overflowed += counters.count(Event::Overflow);
+        }
+
+        stats.checked_out =
+            (stats.gets + stats.attaches).saturating_sub(stats.returns + stats.detaches);
+        stats
     }
 
     /// Attaches `value` to this pool, wrapping
@@ -308,10 +906,33 @@ where
     /// assert_eq!(pool.size(), 1);
     /// ```
     pub fn attach(&self, value: T) -> Recycled<T> {
-        Recycled {
-            value: ManuallyDrop::new(value),
-            pool: self,
-        }
+        self.record(Event::Attach);
+
+        Recycled::new(value, self)
+    }
+
+    /// Attaches `value` to a pool held in an `Arc`.
+    ///
+    /// This is equivalent to `attach`, except for that
+    /// the returned smart pointer holds a reference to
+    /// the pool rather than borrowing it. See `get_owned`.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::Arc;
+    /// use swimmer::Pool;
+    /// let pool: Arc<Pool<u64>> = Arc::new(Pool::new());
+    ///
+    /// let ten = pool.attach_owned(10);
+    /// assert_eq!(pool.size(), 0);
+    ///
+    /// drop(ten);
+    /// assert_eq!(pool.size(), 1);
+    /// ```
+    pub fn attach_owned(self: &Arc<Self>, value: T) -> OwnedRecycled<T> {
+        self.record(Event::Attach);
+
+        OwnedRecycled::new(value, Arc::clone(self))
     }
 
     /// Detatches a value from this pool.
@@ -334,36 +955,868 @@ where
     /// assert_eq!(pool.size(), 9);
     /// ```
     pub fn detached(&self) -> T {
-        self.get_raw_value()
+        let value = self.get_raw_value();
+        self.record(Event::Detach);
+        value
     }
 
-    fn create(&self) -> T {
-        if let Some(supplier) = self.settings.supplier.as_ref() {
-            supplier()
-        } else {
-            T::new()
-        }
+    /// Ensures that the calling thread's buffer holds
+    /// at least `len` objects, so that they can be retrieved
+    /// without accessing the global buffer.
+    ///
+    /// Objects are moved from the global buffer first; the
+    /// rest are initialized. The buffer is never filled beyond
+    /// the pool's `max_size` or `max_total_size`, and this
+    /// function does nothing unless the pool uses `Storage::ThreadLocal`.
+    ///
+    /// # Panics
+    /// Panics if initializing an object fails,
+    /// like `get`.
+    ///
+    /// # Examples
+    /// ```
+    /// use swimmer::Pool;
+    /// let pool: Pool<String> = Pool::with_size(4);
+    ///
+    /// pool.prefill_local(16);
+    /// assert_eq!(pool.local_size(), 16);
+    /// assert_eq!(pool.global_size(), 0);
+    /// ```
+    pub fn prefill_local(&self, len: usize) {
+        self.fill_local(self.local(), len);
     }
 
-    fn return_value(&self, mut value: T) {
-        value.recycle();
-        self.values.get_or(|| init()).borrow_mut().push(value);
+    /// Ensures that the global buffer holds at least
+    /// `len` objects, initializing new objects as needed.
+    ///
+    /// The objects can be retrieved by any thread. The
+    /// global buffer is never filled beyond the pool's
+    /// `max_total_size`.
+    ///
+    /// # Panics
+    /// Panics if initializing an object fails,
+    /// like `get`.
+    ///
+    /// # Examples
+    /// ```
+    /// use swimmer::Pool;
+    /// let pool: Pool<String> = Pool::with_size(4);
+    ///
+    /// pool.reserve(16);
+    /// assert_eq!(pool.global_size(), 16);
+    /// ```
+    pub fn reserve(&self, len: usize) {
+        let count = len.saturating_sub(self.global.len());
+        let mut values = self.create_stored(count);
+        self.global.extend(values.drain(..));
     }
 
-    fn get_raw_value(&self) -> T {
-        self.values
-            .get_or(|| init())
-            .borrow_mut()
-            .pop()
-            .unwrap_or_else(|| self.create())
+    /// Retrieves `count` values from the pool at once.
+    ///
+    /// This is equivalent to calling `get` `count` times,
+    /// but it only accesses the pool's buffers once.
+    ///
+    /// # Examples
+    /// ```
+    /// use swimmer::Pool;
+    /// let pool: Pool<Vec<u8>> = Pool::with_size(64);
+    ///
+    /// let buffers = pool.get_many(64);
+    /// assert_eq!(buffers.len(), 64);
+    /// assert_eq!(pool.size(), 0);
+    /// ```
+    pub fn get_many(&self, count: usize) -> Vec<Recycled<'_, T>> {
+        self.get_raw_values(count)
+            .into_iter()
+            .map(|value| Recycled::new(value, self))
+            .collect()
     }
-}
-
-fn init<T>() -> Box<RefCell<Vec<T>>> {
-    Box::new(RefCell::new(vec![]))
-}
 
-/// A smart pointer which returns the contained
+    /// Adds objects which were created outside
+    /// of the pool to the pool.
+    ///
+    /// Like the starting objects, the objects are placed
+    /// into the global buffer. They are not recycled first.
+    /// Objects which don't fit into the pool because of
+    /// `PoolBuilder::with_max_total_size` are dropped.
+    ///
+    /// # Examples
+    /// ```
+    /// use swimmer::Pool;
+    /// let pool: Pool<Vec<u8>> = Pool::new();
+    ///
+    /// pool.extend((0..4).map(|_| Vec::with_capacity(1024)));
+    /// assert_eq!(pool.size(), 4);
+    /// assert_eq!(pool.get().capacity(), 1024);
+    /// ```
+    pub fn extend<I>(&self, values: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let since = self.settings.idle_timeout.map(|_| Instant::now());
+        let mut kept = vec![];
+
+        for value in values {
+            if self.reserve_slot() {
+                kept.push(Idle { value, since });
+            } else {
+                self.record(Event::Overflow);
+                self.drop_value(value);
+            }
+        }
+
+        self.global.extend(kept.drain(..));
+    }
+
+    /// Returns multiple values to the pool at once.
+    ///
+    /// This is equivalent to dropping each value,
+    /// but it only accesses the pool's buffers once.
+    /// Values retrieved from a different pool are
+    /// returned to that pool instead.
+    ///
+    /// # Examples
+    /// ```
+    /// use swimmer::Pool;
+    /// let pool: Pool<String> = Pool::new();
+    ///
+    /// let strings = pool.get_many(16);
+    /// pool.recycle_all(strings);
+    /// assert_eq!(pool.size(), 16);
+    /// ```
+    pub fn recycle_all<'a, I>(&'a self, values: I)
+    where
+        I: IntoIterator<Item = Recycled<'a, T>>,
+    {
+        let local = self.active_local();
+        self.apply_trim(local);
+        let now = self.evict_local(local);
+
+        let mut kept = vec![];
+        for value in values {
+            if !std::ptr::eq(value.pool, self) {
+                drop(value);
+                continue;
+            }
+
+            let (value, origin) = Recycled::into_parts(value);
+            let value = match self.prepare_return(local, value, now) {
+                Some(value) => value,
+                None => continue,
+            };
+            match origin {
+                Some(origin) if !Arc::ptr_eq(&origin, &local.remote) => {
+                    self.return_remote(local, &origin, value);
+                }
+                _ => kept.push(value),
+            }
+        }
+
+        if !self.settings.storage.is_thread_local() {
+            self.global.extend(kept.drain(..));
+            return;
+        }
+        if self.settings.return_policy == ReturnPolicy::Global {
+            let overflow = kept.split_off(kept.len().min(self.global_room()));
+            self.global.extend(kept.drain(..));
+            self.overflow(local, overflow);
+            return;
+        }
+
+        let mut values = local.values.borrow_mut();
+        values.append(&mut kept);
+        let mut overflow = vec![];
+        if values.len() > self.settings.max_size {
+            overflow = self.spill(&mut values);
+        }
+        local.sync(&values);
+        drop(values);
+        self.overflow(local, overflow);
+    }
+
+    /// Shrinks the pool to at most `size` objects,
+    /// dropping the surplus objects.
+    ///
+    /// Objects are first dropped from the global buffer,
+    /// then from the calling thread's buffer. Other threads'
+    /// buffers can't be accessed from this thread, so if the
+    /// pool is still too large, the remaining threads are
+    /// asked to shrink their buffers the next time they use
+    /// the pool. Until then, `Pool::size` may still exceed `size`.
+    ///
+    /// # Examples
+    /// ```
+    /// use swimmer::Pool;
+    /// let pool: Pool<Vec<u8>> = Pool::with_size(64);
+    ///
+    /// pool.shrink_to(16);
+    /// assert_eq!(pool.size(), 16);
+    /// ```
+    pub fn shrink_to(&self, size: usize) {
+        let mut excess = self.size().saturating_sub(size);
+
+        let removed = self.global.remove(excess);
+        excess -= self.discard(removed);
+
+        let local = self.local();
+        if excess > 0 {
+            let len = self.local_size().saturating_sub(excess);
+            excess -= self.truncate_local(local, len);
+        }
+
+        for info in self.locals().iter() {
+            if excess == 0 {
+                break;
+            }
+            if Arc::ptr_eq(info, &local.info) {
+                continue;
+            }
+
+            let size = info.len();
+            let count = excess.min(size);
+            if count > 0 {
+                info.trim_to
+                    .fetch_min(size - count, AtomicOrdering::Relaxed);
+                excess -= count;
+            }
+        }
+    }
+
+    /// Drops all objects in the pool.
+    ///
+    /// This is equivalent to `shrink_to(0)`; see
+    /// its documentation for how objects held by
+    /// other threads are handled.
+    ///
+    /// # Examples
+    /// ```
+    /// use swimmer::Pool;
+    /// let pool: Pool<String> = Pool::with_size(16);
+    ///
+    /// pool.clear();
+    /// assert_eq!(pool.size(), 0);
+    /// ```
+    pub fn clear(&self) {
+        self.shrink_to(0);
+    }
+
+    /// Drops all objects in the global buffer and in
+    /// the calling thread's buffer which have been idle
+    /// for longer than the pool's idle timeout.
+    ///
+    /// Expired objects are also evicted lazily whenever
+    /// a thread retrieves or returns an object, so calling
+    /// this function is only needed to free memory in a pool
+    /// which isn't being used. Objects held by other threads'
+    /// buffers are only evicted by those threads.
+    ///
+    /// Does nothing if the pool has no idle timeout.
+    /// See `PoolBuilder::with_idle_timeout`.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::thread;
+    /// use std::time::Duration;
+    /// use swimmer::Pool;
+    ///
+    /// let pool: Pool<String> = swimmer::builder()
+    ///     .with_starting_size(8)
+    ///     .with_idle_timeout(Duration::from_millis(1))
+    ///     .build();
+    ///
+    /// thread::sleep(Duration::from_millis(5));
+    /// pool.evict_idle();
+    /// assert_eq!(pool.size(), 0);
+    /// ```
+    pub fn evict_idle(&self) {
+        let timeout = match self.settings.idle_timeout {
+            Some(timeout) => timeout,
+            None => return,
+        };
+        let now = Instant::now();
+
+        let expired = self.global.take_expired(timeout, now);
+        self.evict(expired);
+
+        let local = self.local();
+        let expired = {
+            let mut values = local.values.borrow_mut();
+            local.collect_returned(&mut values);
+            let expired = take_expired(&mut values, timeout, now);
+            local.sync(&values);
+            expired
+        };
+        self.evict(expired);
+    }
+
+    /// Shrinks the calling thread's buffer to at most
+    /// `len` objects, dropping the least recently
+    /// returned ones.
+    ///
+    /// # Examples
+    /// ```
+    /// use swimmer::Pool;
+    /// let pool: Pool<String> = Pool::new();
+    ///
+    /// let values: Vec<_> = (0..8).map(|_| pool.get()).collect();
+    /// drop(values);
+    /// assert_eq!(pool.local_size(), 8);
+    ///
+    /// pool.trim_local(2);
+    /// assert_eq!(pool.local_size(), 2);
+    /// assert_eq!(pool.size(), 2);
+    /// ```
+    pub fn trim_local(&self, len: usize) {
+        self.truncate_local(self.local(), len);
+    }
+
+    pub(crate) fn from_parts(mut settings: PoolBuilder<T>, values: Vec<T>) -> Pool<T> {
+        let peak_size = PeakSize::default();
+        peak_size.update(values.len());
+
+        let since = settings.idle_timeout.map(|_| Instant::now());
+        let mut values: Vec<Idle<T>> = values
+            .into_iter()
+            .map(|value| Idle { value, since })
+            .collect();
+
+        let global = Box::new(Shared::new(settings.storage, settings.shard_key.take()));
+        global.extend(values.drain(..));
+
+        Pool {
+            values: CachedThreadLocal::new(),
+            locals: Mutex::new(vec![]),
+            total: AtomicUsize::new(global.len()),
+            global,
+            rejected: AtomicUsize::new(0),
+            peak_size,
+            settings,
+            alive: Arc::new(Mutex::new(true)),
+        }
+    }
+
+    /// Returns the list which objects retrieved by the
+    /// calling thread should be returned to, if any.
+    pub(crate) fn origin(&self) -> Option<Arc<Remote<T>>> {
+        if self.settings.return_policy == ReturnPolicy::Origin
+            && self.settings.storage.is_thread_local()
+        {
+            Some(Arc::clone(&self.local().remote))
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn return_value(&self, value: T, origin: Option<Arc<Remote<T>>>) {
+        let local = self.active_local();
+        self.apply_trim(local);
+        let now = self.evict_local(local);
+
+        let value = match self.prepare_return(local, value, now) {
+            Some(value) => value,
+            None => return,
+        };
+
+        if let Some(origin) = origin {
+            if !Arc::ptr_eq(&origin, &local.remote) {
+                self.return_remote(local, &origin, value);
+                return;
+            }
+        }
+
+        if !self.settings.storage.is_thread_local() {
+            self.global.push(value);
+            return;
+        }
+        if self.settings.return_policy == ReturnPolicy::Global {
+            self.push_global(local, value);
+            return;
+        }
+
+        let max_size = self.settings.max_size;
+        let mut values = local.values.borrow_mut();
+
+        let mut overflow = vec![];
+        if values.len() >= max_size {
+            overflow = self.spill(&mut values);
+        }
+
+        if values.len() < max_size {
+            values.push(value);
+        } else {
+            overflow.push(value);
+        }
+
+        local.sync(&values);
+        drop(values);
+        self.overflow(local, overflow);
+    }
+
+    /// Returns `value` to the thread which retrieved it,
+    /// or to the global buffer if that thread has exited.
+    fn return_remote(&self, local: &Local<T>, origin: &Remote<T>, value: Idle<T>) {
+        // If the origin thread has exited, nothing
+        // would take the object from its list.
+        if !origin.info.has_exit_hook.load(AtomicOrdering::Relaxed) {
+            self.push_global(local, value);
+            return;
+        }
+
+        origin.push(value);
+
+        // The thread may have exited in the meantime, after
+        // its exit hook emptied the list. Since the hook clears
+        // the flag before locking the list, this is then visible.
+        if !origin.info.has_exit_hook.load(AtomicOrdering::Relaxed) {
+            for value in origin.take() {
+                self.push_global(local, value);
+            }
+        }
+    }
+
+    /// Fills `local` up to `len` objects. See `prefill_local`.
+    fn fill_local(&self, local: &Local<T>, len: usize) {
+        if !self.settings.storage.is_thread_local() {
+            return;
+        }
+
+        let len = len.min(self.settings.max_size);
+        let missing = len.saturating_sub(local.values.borrow().len());
+        if missing == 0 {
+            return;
+        }
+
+        // Collect the objects before borrowing the buffer,
+        // since the supplier may use the pool as well.
+        let now = self.settings.idle_timeout.map(|_| Instant::now());
+        let mut added = self.take_global(missing, now);
+        let created = self.create_stored(missing - added.len());
+        added.extend(created);
+
+        let mut values = local.values.borrow_mut();
+        values.append(&mut added);
+        local.sync(&values);
+    }
+
+    /// Initializes up to `count` objects to be stored in
+    /// the pool, reserving a slot for each of them.
+    fn create_stored(&self, count: usize) -> Vec<Idle<T>> {
+        let count = match self.settings.max_total_size {
+            Some(max_total_size) => count.min(max_total_size.saturating_sub(self.size())),
+            None => count,
+        };
+
+        // Initialize all objects before reserving slots,
+        // so that the size stays correct if this panics.
+        let values: Vec<T> = (0..count)
+            .map(|_| self.settings.try_create().expect(builder::CREATE_FAILED))
+            .collect();
+
+        let since = self.settings.idle_timeout.map(|_| Instant::now());
+        let mut stored = Vec::with_capacity(values.len());
+        for value in values {
+            if self.reserve_slot() {
+                stored.push(Idle { value, since });
+            } else {
+                // Other threads filled the pool in the meantime.
+                self.drop_value(value);
+            }
+        }
+        stored
+    }
+
+    /// Recycles and validates a returned value and reserves
+    /// a slot for it. Returns `None` if the value was dropped.
+    fn prepare_return(
+        &self,
+        local: &Local<T>,
+        mut value: T,
+        now: Option<Instant>,
+    ) -> Option<Idle<T>> {
+        local.info.counters.record(Event::Return);
+        builder::run_hook(&self.settings.return_hook, &mut value);
+        self.settings.recycle(&mut value);
+
+        if !self.settings.validate(&value) {
+            self.rejected.fetch_add(1, AtomicOrdering::Relaxed);
+            self.drop_value(value);
+            return None;
+        }
+
+        if !self.reserve_slot() {
+            // The pool is full; drop the value.
+            local.info.counters.record(Event::Overflow);
+            self.drop_value(value);
+            return None;
+        }
+
+        Some(Idle { value, since: now })
+    }
+
+    fn get_raw_values(&self, count: usize) -> Vec<T> {
+        let local = self.active_local();
+        self.apply_trim(local);
+        let now = self.evict_local(local);
+
+        let mut taken = Vec::with_capacity(count);
+        if self.settings.storage.is_thread_local() {
+            let mut values = local.values.borrow_mut();
+            if values.len() < count {
+                values.append(&mut local.remote.take());
+            }
+            let start = values.len().saturating_sub(count);
+            taken.extend(values.drain(start..));
+            local.sync(&values);
+            drop(values);
+
+            let missing = count - taken.len();
+            taken.append(&mut self.take_global(missing, now));
+        } else {
+            taken = self.take_global(count, now);
+        }
+        self.total.fetch_sub(taken.len(), AtomicOrdering::Relaxed);
+
+        let misses = count - taken.len();
+        let mut values: Vec<T> = taken.into_iter().map(|idle| idle.value).collect();
+        values
+            .extend((0..misses).map(|_| self.settings.try_create().expect(builder::CREATE_FAILED)));
+
+        // Only count the objects once they exist, so that
+        // a panicking supplier doesn't skew the statistics.
+        local.info.counters.record_many(Event::Get, count);
+        local.info.counters.record_many(Event::Miss, misses);
+
+        for value in &mut values {
+            builder::run_hook(&self.settings.checkout_hook, value);
+        }
+        values
+    }
+
+    fn get_raw_value(&self) -> T {
+        self.try_get_raw_value().expect(builder::CREATE_FAILED)
+    }
+
+    fn try_get_raw_value(&self) -> Result<T, CreateError> {
+        let local = self.active_local();
+        self.apply_trim(local);
+        let now = self.evict_local(local);
+
+        let value = if self.settings.storage.is_thread_local() {
+            let mut values = local.values.borrow_mut();
+            if values.is_empty() {
+                values.append(&mut local.remote.take());
+            }
+            let value = values.pop();
+            local.sync(&values);
+            drop(values);
+            value.or_else(|| self.refill(now))
+        } else {
+            self.pop_global(now)
+        };
+
+        let mut value = match value {
+            Some(value) => {
+                self.total.fetch_sub(1, AtomicOrdering::Relaxed);
+                value.value
+            }
+            None => {
+                // Only count the object once it exists, since a
+                // failed `try_get` doesn't check anything out.
+                let value = self.settings.try_create()?;
+                local.info.counters.record(Event::Miss);
+                value
+            }
+        };
+        local.info.counters.record(Event::Get);
+
+        builder::run_hook(&self.settings.checkout_hook, &mut value);
+        Ok(value)
+    }
+
+    /// Shrinks the calling thread's buffer to at most `len`
+    /// objects, dropping the least recently returned ones.
+    /// Returns the number of dropped objects.
+    fn truncate_local(&self, local: &Local<T>, len: usize) -> usize {
+        let removed: Vec<Idle<T>> = {
+            let mut values = local.values.borrow_mut();
+            local.collect_returned(&mut values);
+            let count = values.len().saturating_sub(len);
+            let removed = values.drain(..count).collect();
+            local.sync(&values);
+            removed
+        };
+
+        // Drop the objects only after the buffer
+        // is no longer borrowed.
+        self.discard(removed)
+    }
+
+    /// Shrinks the calling thread's buffer if
+    /// another thread requested it using `shrink_to`.
+    fn apply_trim(&self, local: &Local<T>) {
+        if local.info.trim_to.load(AtomicOrdering::Relaxed) != NO_TRIM {
+            let len = local.info.trim_to.swap(NO_TRIM, AtomicOrdering::Relaxed);
+            self.truncate_local(local, len);
+        }
+    }
+
+    /// Drops objects from the front of the calling thread's
+    /// buffer which have been idle for longer than the idle
+    /// timeout. Returns the current time if the pool has an
+    /// idle timeout.
+    ///
+    /// Returned objects are pushed to the back of the buffer,
+    /// but objects moved from the global buffer may be older
+    /// than the ones before them. This stops at the first
+    /// object which hasn't expired, so such objects are only
+    /// dropped by `evict_idle` or once they reach the front.
+    fn evict_local(&self, local: &Local<T>) -> Option<Instant> {
+        let timeout = self.settings.idle_timeout?;
+        let now = Instant::now();
+
+        let removed: Vec<Idle<T>> = {
+            let mut values = local.values.borrow_mut();
+            local.collect_returned(&mut values);
+            let count = values
+                .iter()
+                .take_while(|idle| idle.is_expired(timeout, now))
+                .count();
+            if count == 0 {
+                return Some(now);
+            }
+
+            let removed = values.drain(..count).collect();
+            local.sync(&values);
+            removed
+        };
+
+        self.evict(removed);
+        Some(now)
+    }
+
+    /// Drops objects which have been idle for
+    /// too long, returning their number.
+    fn evict(&self, values: Vec<Idle<T>>) -> usize {
+        self.local()
+            .info
+            .counters
+            .record_many(Event::Evict, values.len());
+        self.discard(values)
+    }
+
+    /// Drops objects which were removed from
+    /// the pool's buffers, returning their number.
+    fn discard(&self, values: Vec<Idle<T>>) -> usize {
+        let count = values.len();
+        self.total.fetch_sub(count, AtomicOrdering::Relaxed);
+
+        for idle in values {
+            self.drop_value(idle.value);
+        }
+        count
+    }
+
+    fn drop_value(&self, mut value: T) {
+        builder::run_hook(&self.settings.drop_hook, &mut value);
+    }
+
+    /// Increments the total number of stored objects,
+    /// returning `false` without doing so if
+    /// the pool is already at its maximum size.
+    fn reserve_slot(&self) -> bool {
+        let total = self.total.fetch_add(1, AtomicOrdering::Relaxed);
+
+        match self.settings.max_total_size {
+            Some(max_total_size) if total >= max_total_size => {
+                self.total.fetch_sub(1, AtomicOrdering::Relaxed);
+                false
+            }
+            _ => {
+                self.peak_size.update(total + 1);
+                true
+            }
+        }
+    }
+
+    /// Moves the least recently returned objects
+    /// of `values` into the global buffer, leaving
+    /// half of the maximum size.
+    ///
+    /// Returns the objects which didn't fit into the
+    /// global buffer, which the caller should pass to
+    /// `overflow` once `values` is no longer borrowed.
+    fn spill(&self, values: &mut Vec<Idle<T>>) -> Vec<Idle<T>> {
+        let count = values.len() - self.settings.max_size / 2;
+        let room = self.global_room();
+
+        let overflow = values.drain(..count.saturating_sub(room)).collect();
+        self.global.extend(values.drain(..count.min(room)));
+        overflow
+    }
+
+    /// Moves `value` into the global buffer,
+    /// or drops it if the buffer is full.
+    fn push_global(&self, local: &Local<T>, value: Idle<T>) {
+        if self.global_room() > 0 {
+            self.global.push(value);
+        } else {
+            self.overflow(local, vec![value]);
+        }
+    }
+
+    /// Returns how many more objects threads may move
+    /// into the global buffer. See `PoolBuilder::with_max_size`.
+    fn global_room(&self) -> usize {
+        self.settings
+            .global_limit()
+            .saturating_sub(self.global.len())
+    }
+
+    /// Drops objects which didn't fit into the pool.
+    fn overflow(&self, local: &Local<T>, values: Vec<Idle<T>>) {
+        local
+            .info
+            .counters
+            .record_many(Event::Overflow, values.len());
+        self.discard(values);
+    }
+
+    /// Refills the calling thread's buffer from the
+    /// global buffer, returning one of the moved objects.
+    /// Returns `None` if the global buffer has no
+    /// objects which haven't expired.
+    fn refill(&self, now: Option<Instant>) -> Option<Idle<T>> {
+        let mut moved = self.take_global(self.settings.max_size / 2 + 1, now);
+        let value = moved.pop();
+
+        let local = self.local();
+        let mut values = local.values.borrow_mut();
+        values.append(&mut moved);
+        local.sync(&values);
+        value
+    }
+
+    /// Removes up to `count` objects from the global
+    /// buffer, evicting the expired ones rather than
+    /// returning them.
+    fn take_global(&self, count: usize, now: Option<Instant>) -> Vec<Idle<T>> {
+        let mut values = Vec::with_capacity(count);
+        self.global.pop_into(&mut values, count);
+
+        if let (Some(timeout), Some(now)) = (self.settings.idle_timeout, now) {
+            let expired = take_expired(&mut values, timeout, now);
+            self.evict(expired);
+        }
+        values
+    }
+
+    /// Pops an object from the global buffer when
+    /// threads don't have their own buffers, evicting
+    /// expired objects along the way.
+    fn pop_global(&self, now: Option<Instant>) -> Option<Idle<T>> {
+        let (timeout, now) = match (self.settings.idle_timeout, now) {
+            (Some(timeout), Some(now)) => (timeout, now),
+            _ => return self.global.pop(),
+        };
+
+        let mut expired = vec![];
+        let value = loop {
+            match self.global.pop() {
+                Some(idle) if idle.is_expired(timeout, now) => expired.push(idle),
+                value => break value,
+            }
+        };
+
+        self.evict(expired);
+        value
+    }
+
+    /// Drops a returned value which can't be kept,
+    /// counting it as returned.
+    pub(crate) fn discard_returned(&self, value: T) {
+        self.record(Event::Return);
+        self.drop_value(value);
+    }
+
+    pub(crate) fn record(&self, event: Event) {
+        self.local().info.counters.record(event);
+    }
+
+    fn local(&self) -> &Local<T> {
+        let local = self.values.get_or(|| {
+            let info = Arc::new(LocalInfo {
+                thread: thread::current().id(),
+                size: AtomicUsize::new(0),
+                returned: AtomicUsize::new(0),
+                trim_to: AtomicUsize::new(NO_TRIM),
+                has_exit_hook: AtomicBool::new(false),
+                counters: Counters::default(),
+            });
+            self.locals().push(Arc::clone(&info));
+
+            Box::new(Local {
+                values: RefCell::new(vec![]),
+                remote: Arc::new(Remote {
+                    values: Mutex::new(vec![]),
+                    info: Arc::clone(&info),
+                }),
+                nodes: RefCell::new(vec![]),
+                warm_up: Cell::new(false),
+                info,
+            })
+        });
+
+        // Also true if the buffer was created by a thread
+        // which has exited, and is now reused by this thread.
+        if !local.info.has_exit_hook.load(AtomicOrdering::Relaxed) {
+            self.init_local(local);
+        }
+        local
+    }
+
+    /// Prepares a buffer for use by the calling thread.
+    #[cold]
+    fn init_local(&self, local: &Local<T>) {
+        if self.settings.storage.is_thread_local() {
+            reclaim::register(
+                &self.alive,
+                local,
+                &self.global,
+                self.settings.global_limit(),
+            );
+            local.warm_up.set(self.settings.thread_starting_size > 0);
+        } else {
+            // The buffer is always empty.
+            local
+                .info
+                .has_exit_hook
+                .store(true, AtomicOrdering::Relaxed);
+        }
+    }
+
+    /// Like `local`, but first fills the buffer up to
+    /// the thread starting size if the calling thread
+    /// hasn't retrieved or returned any objects yet.
+    fn active_local(&self) -> &Local<T> {
+        let local = self.local();
+        if local.warm_up.get() {
+            self.warm_up(local);
+        }
+        local
+    }
+
+    #[cold]
+    fn warm_up(&self, local: &Local<T>) {
+        local.warm_up.set(false);
+        self.fill_local(local, self.settings.thread_starting_size);
+    }
+
+    fn locals(&self) -> MutexGuard<'_, Vec<Arc<LocalInfo>>> {
+        self.locals.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// A smart pointer which returns the contained
 /// object to its pool once dropped.
 ///
 /// Objects of this type are obtained using `Pool::get`.
@@ -373,6 +1826,184 @@ where
 {
     value: ManuallyDrop<T>,
     pool: &'a Pool<T>,
+    /// See `ReturnPolicy::Origin`.
+    origin: Option<Arc<Remote<T>>>,
+}
+
+impl<'a, T> Recycled<'a, T>
+where
+    T: Recyclable,
+{
+    fn new(value: T, pool: &'a Pool<T>) -> Self {
+        Recycled {
+            value: ManuallyDrop::new(value),
+            pool,
+            origin: pool.origin(),
+        }
+    }
+
+    /// Takes ownership of the contained object
+    /// without returning it to the pool.
+    ///
+    /// This is an associated function rather than
+    /// a method so that it doesn't conflict with
+    /// methods on `T`.
+    ///
+    /// # Examples
+    /// ```
+    /// use swimmer::{Pool, Recycled};
+    /// let pool: Pool<String> = Pool::with_size(1);
+    ///
+    /// let mut string = pool.get();
+    /// string.push_str("test");
+    ///
+    /// let string: String = Recycled::into_inner(string);
+    /// assert_eq!(string, "test");
+    /// assert_eq!(pool.size(), 0);
+    /// ```
+    pub fn into_inner(this: Self) -> T {
+        this.pool.record(Event::Detach);
+        Self::take(this)
+    }
+
+    /// Takes the contained object out of the pool
+    /// and leaks it, returning a mutable reference
+    /// which lives as long as the pool.
+    ///
+    /// # Examples
+    /// ```
+    /// use swimmer::{Pool, Recycled};
+    /// let pool: Pool<String> = Pool::new();
+    ///
+    /// let string: &mut String = Recycled::leak(pool.get());
+    /// string.push_str("test");
+    /// assert_eq!(pool.size(), 0);
+    /// ```
+    pub fn leak(this: Self) -> &'a mut T
+    where
+        T: 'a,
+    {
+        Box::leak(Box::new(Self::into_inner(this)))
+    }
+
+    /// Makes a new guard for a part of the object,
+    /// such as one of its fields. The whole object
+    /// is still returned to the pool once the new
+    /// guard is dropped.
+    ///
+    /// This is useful for handing out only a part of a
+    /// pooled object, similar to `RefMut::map`.
+    ///
+    /// # Examples
+    /// ```
+    /// use swimmer::{Pool, Recyclable, Recycled};
+    ///
+    /// struct Frame {
+    ///     id: u64,
+    ///     data: Vec<u8>,
+    /// }
+    ///
+    /// impl Recyclable for Frame {
+    ///     fn new() -> Self {
+    ///         Frame { id: 0, data: Vec::with_capacity(1024) }
+    ///     }
+    ///
+    ///     fn recycle(&mut self) {
+    ///         self.id = 0;
+    ///         self.data.clear();
+    ///     }
+    /// }
+    ///
+    /// let pool: Pool<Frame> = Pool::new();
+    ///
+    /// let mut data = Recycled::map(pool.get(), |frame| &mut frame.data);
+    /// data.extend_from_slice(b"hello");
+    /// assert_eq!(&data[..], b"hello");
+    ///
+    /// drop(data);
+    /// assert_eq!(pool.size(), 1);
+    /// ```
+    pub fn map<U, F>(mut this: Self, f: F) -> MappedRecycled<'a, T, U>
+    where
+        F: FnOnce(&mut T) -> &mut U,
+    {
+        let value: *mut T = &mut *this.value;
+        // Convert the result to a pointer right away, since
+        // moving `this` invalidates references into the object.
+        let target: *mut U = f(unsafe {
+            // Safe because `value` points to
+            // the object held by `this`.
+            &mut *value
+        });
+        MappedRecycled::new(this, value, target)
+    }
+
+    /// Returns the pool which the object
+    /// will be returned to.
+    ///
+    /// # Examples
+    /// ```
+    /// use swimmer::{Pool, Recycled};
+    /// let pool: Pool<String> = Pool::new();
+    ///
+    /// let string = pool.get();
+    /// assert!(std::ptr::eq(Recycled::pool(&string), &pool));
+    /// ```
+    pub fn pool(this: &Self) -> &'a Pool<T> {
+        this.pool
+    }
+
+    /// Takes the contained object without
+    /// returning it to the pool or recording
+    /// an event.
+    fn take(this: Self) -> T {
+        let (value, _) = Self::into_parts(this);
+        value
+    }
+
+    /// Takes the contained object and its
+    /// origin, like `take`.
+    fn into_parts(mut this: Self) -> (T, Origin<T>) {
+        let origin = this.origin.take();
+
+        let value = unsafe {
+            // Safe because `this` is forgotten
+            // without running its destructor.
+            ManuallyDrop::take(&mut this.value)
+        };
+        mem::forget(this);
+        (value, origin)
+    }
+}
+
+impl<'a> Recycled<'a, Vec<u8>> {
+    /// Converts the buffer into an immutable view,
+    /// which can be cloned and sliced cheaply. The
+    /// buffer is returned to the pool once every
+    /// view into it has been dropped.
+    ///
+    /// # Examples
+    /// ```
+    /// use swimmer::{Pool, Recycled};
+    /// let pool: Pool<Vec<u8>> = Pool::new();
+    ///
+    /// let mut buf = pool.get();
+    /// buf.extend_from_slice(b"hello world");
+    /// let buf = Recycled::freeze(buf);
+    ///
+    /// let hello = buf.slice(..5);
+    /// drop(buf);
+    /// assert_eq!(&hello[..], b"hello");
+    /// assert_eq!(pool.size(), 0);
+    ///
+    /// drop(hello);
+    /// assert_eq!(pool.size(), 1);
+    /// ```
+    pub fn freeze(this: Self) -> FrozenRecycled<'a> {
+        let pool = this.pool;
+        let (value, origin) = Self::into_parts(this);
+        FrozenRecycled::new(Node::new(value, origin, pool, None))
+    }
 }
 
 impl<'a, T> Drop for Recycled<'a, T>
@@ -383,13 +2014,12 @@ where
         // Return value to pool.
 
         let value = unsafe {
-            // Safe because the value is wrapped in ManuallyDrop,
-            // so the uninitialized memory won't be read from.
-            std::mem::replace(&mut self.value, MaybeUninit::uninit().assume_init())
+            // Safe because `self.value` is never
+            // accessed again after this point.
+            ManuallyDrop::take(&mut self.value)
         };
-        let value = ManuallyDrop::into_inner(value);
 
-        self.pool.return_value(value);
+        self.pool.return_value(value, self.origin.take());
     }
 }
 
@@ -477,6 +2107,31 @@ mod tests {
         assert_impl_all!(Pool<String>, Send, Sync);
     }
 
+    #[test]
+    fn test_keyed_pool_send_and_sync() {
+        assert_impl_all!(KeyedPool<usize, String>, Send, Sync);
+    }
+
+    #[test]
+    fn test_buf_pool_send_and_sync() {
+        assert_impl_all!(BufPool, Send, Sync);
+    }
+
+    #[test]
+    fn test_owned_recycled_send() {
+        assert_impl_all!(OwnedRecycled<String>, Send, Sync);
+    }
+
+    #[test]
+    fn test_shared_recycled_send() {
+        assert_impl_all!(SharedRecycled<String>, Send, Sync);
+    }
+
+    #[test]
+    fn test_frozen_recycled_send() {
+        assert_impl_all!(FrozenRecycled<'static>, Send, Sync);
+    }
+
     #[test]
     fn test_builder() {
         let pool: Pool<String> = builder().with_starting_size(100).build();
@@ -507,4 +2162,124 @@ mod tests {
         assert_eq!(*value, "testbla");
         drop(value);
     }
+
+    #[test]
+    fn test_recycler() {
+        let pool: Pool<String> = builder()
+            .with_recycler(|string: &mut String| string.truncate(4))
+            .build();
+
+        let mut value = pool.get();
+        value.push_str("testbla");
+        drop(value);
+
+        assert_eq!(*pool.get(), "test");
+    }
+
+    #[test]
+    fn test_try_new() {
+        struct Fails;
+
+        impl Recyclable for Fails {
+            fn new() -> Self {
+                Fails
+            }
+
+            fn try_new() -> Result<Self, CreateError> {
+                Err("cannot create".into())
+            }
+
+            fn recycle(&mut self) {}
+        }
+
+        assert!(builder::<Fails>()
+            .with_starting_size(1)
+            .try_build()
+            .is_err());
+
+        let pool: Pool<Fails> = Pool::new();
+        assert!(pool.try_get().is_err());
+
+        drop(pool.attach(Fails));
+        assert!(pool.try_get().is_ok());
+    }
+
+    #[cfg(feature = "stats")]
+    #[test]
+    fn test_stats() {
+        let pool: Pool<String> = builder().with_max_total_size(1).build();
+
+        let attached = pool.attach(String::new());
+        let detached = Recycled::into_inner(pool.get());
+        drop(pool.get());
+        drop(attached);
+
+        let stats = pool.stats();
+        assert_eq!(stats.gets, 2);
+        assert_eq!(stats.misses, 2);
+        assert_eq!(stats.attaches, 1);
+        assert_eq!(stats.detaches, 1);
+        assert_eq!(stats.returns, 2);
+        assert_eq!(stats.overflowed, 1);
+        assert_eq!(stats.checked_out, 0);
+        assert_eq!(stats.size, 1);
+        drop(detached);
+    }
+
+    #[test]
+    fn test_validator() {
+        let pool: Pool<Vec<u8>> = builder()
+            .with_validator(|vec: &Vec<u8>| vec.capacity() < 64)
+            .build();
+
+        let mut small = pool.get();
+        small.reserve_exact(8);
+        let mut large = pool.get();
+        large.reserve_exact(128);
+
+        drop(small);
+        drop(large);
+
+        assert_eq!(pool.size(), 1);
+        assert_eq!(pool.rejected(), 1);
+        assert!(pool.get().capacity() < 64);
+    }
+
+    #[test]
+    fn test_hooks() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let counts = Arc::new([
+            AtomicUsize::new(0),
+            AtomicUsize::new(0),
+            AtomicUsize::new(0),
+            AtomicUsize::new(0),
+        ]);
+        let hook = |i: usize| {
+            let counts = Arc::clone(&counts);
+            move |_: &mut String| {
+                counts[i].fetch_add(1, Ordering::Relaxed);
+            }
+        };
+
+        let pool: Pool<String> = builder()
+            .with_create_hook(hook(0))
+            .with_checkout_hook(hook(1))
+            .with_return_hook(hook(2))
+            .with_drop_hook(hook(3))
+            .with_validator(|string: &String| string.capacity() < 64)
+            .build();
+
+        let mut large = pool.get();
+        large.reserve_exact(128);
+        let small = pool.get();
+        drop(large);
+        drop(small);
+        drop(pool.get());
+        drop(pool);
+
+        let counts: Vec<usize> = counts.iter().map(|c| c.load(Ordering::Relaxed)).collect();
+        assert_eq!(counts, [2, 3, 3, 2]);
+    }
 }
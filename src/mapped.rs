@@ -0,0 +1,227 @@
+use crate::{Pool, Recyclable, Recycled};
+use std::cmp::Ordering;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::marker::PhantomData;
+use std::mem;
+use std::ops::{Deref, DerefMut};
+use std::ptr::NonNull;
+
+/// A smart pointer to a part of a pooled object,
+/// which returns the whole object to its pool
+/// once dropped.
+///
+/// Objects of this type are obtained using `Recycled::map`.
+pub struct MappedRecycled<'a, T, U>
+where
+    T: Recyclable,
+{
+    guard: Recycled<'a, T>,
+    target: Target<U>,
+    _marker: PhantomData<&'a mut U>,
+}
+
+/// Where the target of a `MappedRecycled` lives.
+enum Target<U> {
+    /// Inside the object, at the given offset. The object
+    /// moves along with the guard, so a pointer
+    /// to it can't be stored.
+    Field(usize),
+    /// Outside the object, such as in
+    /// a heap allocation owned by it.
+    Outside(NonNull<U>),
+}
+
+impl<U> Target<U> {
+    /// Returns a pointer to the target, given a
+    /// pointer to the object at its current address.
+    fn resolve<T>(&self, value: *mut T) -> *mut U {
+        match *self {
+            Target::Field(offset) => value.cast::<u8>().wrapping_add(offset).cast(),
+            Target::Outside(target) => target.as_ptr(),
+        }
+    }
+}
+
+impl<'a, T, U> MappedRecycled<'a, T, U>
+where
+    T: Recyclable,
+{
+    /// Creates a guard pointing to `target`, which was
+    /// derived from a reference to `value`, the object
+    /// held by `guard`.
+    ///
+    /// `target` is a pointer rather than a reference, since
+    /// a reference into the object would be invalidated
+    /// when `guard` is moved into this function.
+    pub(crate) fn new(guard: Recycled<'a, T>, value: *mut T, target: *mut U) -> Self {
+        let offset = (target as usize).wrapping_sub(value as usize);
+        let target = match offset.checked_add(mem::size_of::<U>()) {
+            Some(end) if end <= mem::size_of::<T>() => Target::Field(offset),
+            _ => Target::Outside(unsafe {
+                // Safe because `target` was derived
+                // from a reference.
+                NonNull::new_unchecked(target)
+            }),
+        };
+
+        MappedRecycled {
+            guard,
+            target,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Makes a new guard for a part of the
+    /// borrowed data, such as a field.
+    ///
+    /// See `Recycled::map`.
+    ///
+    /// # Examples
+    /// ```
+    /// use swimmer::{MappedRecycled, Pool, Recycled};
+    /// let pool: Pool<Vec<(u32, String)>> = Pool::new();
+    ///
+    /// let mut pairs = pool.get();
+    /// pairs.push((1, String::from("one")));
+    ///
+    /// let pair = Recycled::map(pairs, |pairs| &mut pairs[0]);
+    /// let name = MappedRecycled::map(pair, |pair| &mut pair.1);
+    /// assert_eq!(*name, "one");
+    /// ```
+    pub fn map<V, F>(mut this: Self, f: F) -> MappedRecycled<'a, T, V>
+    where
+        F: FnOnce(&mut U) -> &mut V,
+    {
+        let value: *mut T = &mut *this.guard.value;
+        // See `Recycled::map`.
+        let target: *mut V = f(unsafe {
+            // Safe because `value` points to the object
+            // held by `this`, which is still alive.
+            &mut *this.target.resolve(value)
+        });
+        MappedRecycled::new(this.guard, value, target)
+    }
+
+    /// Takes ownership of the whole object
+    /// without returning it to the pool.
+    ///
+    /// See `Recycled::into_inner`.
+    pub fn into_inner(this: Self) -> T {
+        Recycled::into_inner(this.guard)
+    }
+
+    /// Returns the pool which the object
+    /// will be returned to.
+    pub fn pool(this: &Self) -> &'a Pool<T> {
+        Recycled::pool(&this.guard)
+    }
+}
+
+// Safe because the guard only gives access to
+// the target, just like `&mut U`, and the object
+// itself is `Send` as required by `Recyclable`.
+unsafe impl<'a, T, U> Send for MappedRecycled<'a, T, U>
+where
+    T: Recyclable,
+    U: Send,
+{
+}
+
+// Safe because a shared reference to the guard
+// only gives shared access to the target and
+// the pool, which is `Sync` if `T` is.
+unsafe impl<'a, T, U> Sync for MappedRecycled<'a, T, U>
+where
+    T: Recyclable + Sync,
+    U: Sync,
+{
+}
+
+impl<'a, T, U> AsRef<U> for MappedRecycled<'a, T, U>
+where
+    T: Recyclable,
+{
+    fn as_ref(&self) -> &U {
+        self
+    }
+}
+
+impl<'a, T, U> AsMut<U> for MappedRecycled<'a, T, U>
+where
+    T: Recyclable,
+{
+    fn as_mut(&mut self) -> &mut U {
+        self
+    }
+}
+
+impl<'a, T, U> Deref for MappedRecycled<'a, T, U>
+where
+    T: Recyclable,
+{
+    type Target = U;
+
+    fn deref(&self) -> &Self::Target {
+        let value = &*self.guard.value as *const T as *mut T;
+        unsafe {
+            // Safe because the target was derived from the
+            // object, which is borrowed as long as the result.
+            // The pointer is only used for reading.
+            &*self.target.resolve(value)
+        }
+    }
+}
+
+impl<'a, T, U> DerefMut for MappedRecycled<'a, T, U>
+where
+    T: Recyclable,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        let value: *mut T = &mut *self.guard.value;
+        unsafe {
+            // Safe because the target was derived from the
+            // object, which is borrowed as long as the result.
+            &mut *self.target.resolve(value)
+        }
+    }
+}
+
+impl<'a, T, U> Display for MappedRecycled<'a, T, U>
+where
+    T: Recyclable,
+    U: Display,
+{
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.as_ref())
+    }
+}
+
+impl<'a, T, U> Debug for MappedRecycled<'a, T, U>
+where
+    T: Recyclable,
+    U: Debug,
+{
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{:?}", self.as_ref())
+    }
+}
+
+impl<'a, T, U> PartialEq<U> for MappedRecycled<'a, T, U>
+where
+    T: Recyclable,
+    U: PartialEq,
+{
+    fn eq(&self, other: &U) -> bool {
+        self.as_ref().eq(other)
+    }
+}
+
+impl<'a, T, U> PartialOrd<U> for MappedRecycled<'a, T, U>
+where
+    T: Recyclable,
+    U: PartialOrd,
+{
+    fn partial_cmp(&self, other: &U) -> Option<Ordering> {
+        self.as_ref().partial_cmp(other)
+    }
+}
@@ -0,0 +1,221 @@
+use crate::shared::Node;
+use crate::stats::Event;
+use crate::{FrozenRecycled, Origin, Pool, Recyclable, Remote};
+use std::cmp::Ordering;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::mem::{self, ManuallyDrop};
+use std::ops::{Deref, DerefMut};
+use std::ptr;
+use std::sync::Arc;
+
+/// A smart pointer which returns the contained
+/// object to its pool once dropped.
+///
+/// Unlike `Recycled`, this type holds an `Arc`
+/// to the pool instead of borrowing it, so it
+/// keeps the pool alive and is not tied to
+/// a lifetime.
+///
+/// Objects of this type are obtained using `Pool::get_owned`.
+pub struct OwnedRecycled<T>
+where
+    T: Recyclable,
+{
+    value: ManuallyDrop<T>,
+    pool: Arc<Pool<T>>,
+    /// See `ReturnPolicy::Origin`.
+    origin: Option<Arc<Remote<T>>>,
+}
+
+impl<T> OwnedRecycled<T>
+where
+    T: Recyclable,
+{
+    pub(crate) fn new(value: T, pool: Arc<Pool<T>>) -> Self {
+        let origin = pool.origin();
+        Self::from_parts(value, pool, origin)
+    }
+
+    pub(crate) fn from_parts(value: T, pool: Arc<Pool<T>>, origin: Option<Arc<Remote<T>>>) -> Self {
+        Self {
+            value: ManuallyDrop::new(value),
+            pool,
+            origin,
+        }
+    }
+
+    /// Takes ownership of the contained object
+    /// without returning it to the pool.
+    ///
+    /// See `Recycled::into_inner`.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::Arc;
+    /// use swimmer::{OwnedRecycled, Pool};
+    /// let pool: Arc<Pool<String>> = Arc::new(Pool::with_size(1));
+    ///
+    /// let string: String = OwnedRecycled::into_inner(pool.get_owned());
+    /// assert_eq!(pool.size(), 0);
+    /// ```
+    pub fn into_inner(this: Self) -> T {
+        this.pool.record(Event::Detach);
+        let (value, _, _) = Self::into_parts(this);
+        value
+    }
+
+    /// Takes the contained object, the pool and the
+    /// origin without returning the object to the pool.
+    pub(crate) fn into_parts(mut this: Self) -> (T, Arc<Pool<T>>, Origin<T>) {
+        let parts = unsafe {
+            // Safe because `this` is forgotten
+            // without running its destructor, so
+            // no field is dropped twice.
+            (
+                ManuallyDrop::take(&mut this.value),
+                ptr::read(&this.pool),
+                this.origin.take(),
+            )
+        };
+        mem::forget(this);
+        parts
+    }
+
+    /// Returns the pool which the object
+    /// will be returned to.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::Arc;
+    /// use swimmer::{OwnedRecycled, Pool};
+    /// let pool: Arc<Pool<String>> = Arc::new(Pool::new());
+    ///
+    /// let string = pool.get_owned();
+    /// assert!(Arc::ptr_eq(OwnedRecycled::pool(&string), &pool));
+    /// ```
+    pub fn pool(this: &Self) -> &Arc<Pool<T>> {
+        &this.pool
+    }
+}
+
+impl OwnedRecycled<Vec<u8>> {
+    /// Converts the buffer into an immutable view,
+    /// which can be cloned and sliced cheaply. The
+    /// buffer is returned to the pool once every
+    /// view into it has been dropped.
+    ///
+    /// See `Recycled::freeze`. Since the view keeps
+    /// the pool alive, it is not tied to a lifetime.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::Arc;
+    /// use std::thread;
+    /// use swimmer::{OwnedRecycled, Pool};
+    /// let pool: Arc<Pool<Vec<u8>>> = Arc::new(Pool::new());
+    ///
+    /// let mut buf = pool.get_owned();
+    /// buf.extend_from_slice(b"hello");
+    /// let buf = OwnedRecycled::freeze(buf);
+    ///
+    /// let handle = thread::spawn(move || buf.len());
+    /// assert_eq!(handle.join().unwrap(), 5);
+    /// assert_eq!(pool.size(), 1);
+    /// ```
+    pub fn freeze(this: Self) -> FrozenRecycled<'static> {
+        let (value, pool, origin) = Self::into_parts(this);
+        let node = Node::with_owner(value, origin, pool);
+        FrozenRecycled::new(node)
+    }
+}
+
+impl<T> Drop for OwnedRecycled<T>
+where
+    T: Recyclable,
+{
+    fn drop(&mut self) {
+        // Return value to pool.
+
+        let value = unsafe {
+            // Safe because `self.value` is never
+            // accessed again after this point.
+            ManuallyDrop::take(&mut self.value)
+        };
+
+        self.pool.return_value(value, self.origin.take());
+    }
+}
+
+impl<T> AsRef<T> for OwnedRecycled<T>
+where
+    T: Recyclable,
+{
+    fn as_ref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> AsMut<T> for OwnedRecycled<T>
+where
+    T: Recyclable,
+{
+    fn as_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+impl<T> Deref for OwnedRecycled<T>
+where
+    T: Recyclable,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for OwnedRecycled<T>
+where
+    T: Recyclable,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.value
+    }
+}
+
+impl<T> Display for OwnedRecycled<T>
+where
+    T: Recyclable + Display,
+{
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.as_ref())
+    }
+}
+
+impl<T> Debug for OwnedRecycled<T>
+where
+    T: Recyclable + Debug,
+{
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{:?}", self.as_ref())
+    }
+}
+
+impl<T> PartialEq<T> for OwnedRecycled<T>
+where
+    T: Recyclable + PartialEq,
+{
+    fn eq(&self, other: &T) -> bool {
+        self.as_ref().eq(other)
+    }
+}
+
+impl<T> PartialOrd<T> for OwnedRecycled<T>
+where
+    T: Recyclable + PartialOrd,
+{
+    fn partial_cmp(&self, other: &T) -> Option<Ordering> {
+        self.as_ref().partial_cmp(other)
+    }
+}
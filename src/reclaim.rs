@@ -0,0 +1,119 @@
+use crate::storage::{Shared, Store};
+use crate::{Local, Recyclable};
+use std::cell::RefCell;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// Whether a pool is still alive. Shared between the
+/// pool and the exit hooks of the threads using it.
+///
+/// The pool sets this to `false` when it is dropped,
+/// while holding the lock, so an exit hook which
+/// holds the lock can safely access the pool.
+pub(crate) type Alive = Arc<Mutex<bool>>;
+
+/// Moves the objects held by a thread's buffer
+/// into a pool's global buffer when the thread exits.
+struct ExitHook {
+    alive: Alive,
+    local: *const (),
+    global: *const (),
+    /// The pool's limit for the global buffer.
+    limit: usize,
+    /// `migrate::<T>` for the pool's `T`.
+    migrate: unsafe fn(*const (), *const (), usize),
+}
+
+impl ExitHook {
+    fn is_alive(&self) -> bool {
+        *self.alive.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// The exit hooks of the current thread,
+/// which run when it is destroyed.
+struct ExitHooks(RefCell<Vec<ExitHook>>);
+
+impl Drop for ExitHooks {
+    fn drop(&mut self) {
+        for hook in self.0.get_mut().drain(..) {
+            let alive = hook.alive.lock().unwrap_or_else(PoisonError::into_inner);
+            if *alive {
+                unsafe {
+                    // Safe because the pool is still alive and
+                    // can't be dropped while we hold the lock.
+                    //
+                    // The thread's buffer can't be in use by another
+                    // thread yet: `thread_local` only frees this thread's
+                    // ID, allowing a new thread to reuse the buffer, in a
+                    // thread-local destructor which was registered before
+                    // `EXIT_HOOKS`, and destructors run in reverse order.
+                    (hook.migrate)(hook.local, hook.global, hook.limit);
+                }
+            }
+        }
+    }
+}
+
+thread_local! {
+    static EXIT_HOOKS: ExitHooks = const { ExitHooks(RefCell::new(Vec::new())) };
+}
+
+/// Registers an exit hook for the calling
+/// thread, which owns `local`.
+///
+/// `global` must not move until `alive` is set to `false`.
+/// At most `limit` objects are moved into `global`.
+pub(crate) fn register<T: Recyclable>(
+    alive: &Alive,
+    local: &Local<T>,
+    global: &Shared<T>,
+    limit: usize,
+) {
+    let hook = ExitHook {
+        alive: Arc::clone(alive),
+        local: local as *const Local<T> as *const (),
+        global: global as *const Shared<T> as *const (),
+        limit,
+        migrate: migrate::<T>,
+    };
+
+    // Fails if the thread is already exiting,
+    // in which case the objects are kept.
+    let _ = EXIT_HOOKS.try_with(|hooks| {
+        let mut hooks = hooks.0.borrow_mut();
+        // Forget the hooks of pools which were dropped.
+        hooks.retain(ExitHook::is_alive);
+        hooks.push(hook);
+    });
+    local.info.has_exit_hook.store(true, Ordering::Relaxed);
+}
+
+/// Moves the objects held by `local` into `global`, until
+/// `global` holds `limit` objects. The least recently
+/// returned objects are left in `local`, to be reused
+/// by the next thread which uses the buffer.
+///
+/// # Safety
+/// `local` must point to a `Local<T>` owned by the
+/// calling thread and `global` to a `Shared<T>`, both
+/// belonging to a pool which is not dropped during the call.
+unsafe fn migrate<T: Recyclable>(local: *const (), global: *const (), limit: usize) {
+    let local = &*(local as *const Local<T>);
+    let global = &*(global as *const Shared<T>);
+
+    // The buffer may be reused by a new thread, which
+    // then needs to register its own exit hook.
+    //
+    // This is cleared before emptying the `Remote` list,
+    // so that a thread which pushes to the list afterwards
+    // sees it and moves the object to the global buffer.
+    local.info.has_exit_hook.store(false, Ordering::Relaxed);
+
+    let mut values = local.values.borrow_mut();
+    values.append(&mut local.remote.take());
+    let count = values.len().min(limit.saturating_sub(global.len()));
+    let start = values.len() - count;
+    global.extend(values.drain(start..));
+    local.sync(&values);
+}
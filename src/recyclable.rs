@@ -1,4 +1,5 @@
 use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, LinkedList, VecDeque};
+use std::error::Error;
 use std::hash::Hash;
 
 macro_rules! num_recyclable_impl {
@@ -15,6 +16,10 @@ macro_rules! num_recyclable_impl {
     };
 }
 
+/// An error returned when initializing
+/// a new object for a pool fails.
+pub type CreateError = Box<dyn Error + Send + Sync>;
+
 /// Indicates that an object can be used
 /// inside a `Pool`.
 ///
@@ -26,6 +31,22 @@ pub trait Recyclable: Send {
     where
         Self: Sized;
 
+    /// Attempts to create a new value of this type.
+    ///
+    /// Pools call this function instead of `new`
+    /// when initializing objects, so types whose
+    /// initialization can fail should override it.
+    /// Errors are returned by `Pool::try_get` and
+    /// `PoolBuilder::try_build`.
+    ///
+    /// The default implementation calls `new`.
+    fn try_new() -> Result<Self, CreateError>
+    where
+        Self: Sized,
+    {
+        Ok(Self::new())
+    }
+
     /// Resets this object, allowing it to
     /// be reused in the future without retaining
     /// its old state.
@@ -0,0 +1,411 @@
+use crate::{OwnedRecycled, Pool, Recyclable, Remote};
+use std::cmp::Ordering;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::marker::PhantomData;
+use std::mem::{self, MaybeUninit};
+use std::ops::Deref;
+use std::process;
+use std::ptr::NonNull;
+use std::sync::atomic::{self, AtomicUsize, Ordering as AtomicOrdering};
+use std::sync::Arc;
+
+/// A checked-out object together with its
+/// reference count.
+///
+/// Nodes are allocated once and then kept by
+/// the pool while they are empty, so sharing an
+/// object doesn't allocate.
+pub(crate) struct Node<T>
+where
+    T: Recyclable,
+{
+    count: AtomicUsize,
+    /// Only initialized while the count is nonzero.
+    value: MaybeUninit<T>,
+    /// See `ReturnPolicy::Origin`.
+    origin: Option<Arc<Remote<T>>>,
+    /// The pool which the object is returned to.
+    pool: *const Pool<T>,
+    /// Keeps the pool alive, unless the
+    /// references to the node borrow it.
+    owner: Option<Arc<Pool<T>>>,
+}
+
+// Safe because `pool` is only dereferenced while the
+// pool is alive, and pools are `Sync`.
+unsafe impl<T> Send for Node<T> where T: Recyclable {}
+
+impl<T> Node<T>
+where
+    T: Recyclable,
+{
+    /// Stores `value` in a node with a count of one, reusing
+    /// an empty node of the calling thread if it has one.
+    ///
+    /// `owner` must be `None` or hold `pool`.
+    pub(crate) fn new(
+        value: T,
+        origin: Option<Arc<Remote<T>>>,
+        pool: &Pool<T>,
+        owner: Option<Arc<Pool<T>>>,
+    ) -> NonNull<Node<T>> {
+        let node = Node {
+            count: AtomicUsize::new(1),
+            value: MaybeUninit::new(value),
+            origin,
+            pool,
+            owner,
+        };
+
+        let node = match pool.local().nodes.borrow_mut().pop() {
+            Some(mut empty) => {
+                *empty = node;
+                empty
+            }
+            None => Box::new(node),
+        };
+        NonNull::from(Box::leak(node))
+    }
+
+    /// Like `new`, but the node keeps `pool` alive
+    /// instead of borrowing it.
+    pub(crate) fn with_owner(
+        value: T,
+        origin: Option<Arc<Remote<T>>>,
+        pool: Arc<Pool<T>>,
+    ) -> NonNull<Node<T>> {
+        unsafe {
+            // Safe because moving the `Arc` into
+            // the node doesn't move the pool.
+            let borrowed = &*Arc::as_ptr(&pool);
+            Node::new(value, origin, borrowed, Some(pool))
+        }
+    }
+
+    /// Returns the object held by the node.
+    ///
+    /// # Safety
+    /// The count must be nonzero for as long
+    /// as the reference is used.
+    pub(crate) unsafe fn value<'n>(node: NonNull<Node<T>>) -> &'n T {
+        (*node.as_ptr()).value.assume_init_ref()
+    }
+
+    /// Returns the number of references to the node.
+    ///
+    /// # Safety
+    /// The caller must hold a reference.
+    pub(crate) unsafe fn count(node: NonNull<Node<T>>) -> usize {
+        (*node.as_ptr()).count.load(AtomicOrdering::Acquire)
+    }
+
+    /// Adds a reference to the node.
+    ///
+    /// # Safety
+    /// The caller must hold a reference.
+    pub(crate) unsafe fn acquire(node: NonNull<Node<T>>) {
+        // Like `Arc`, abort rather than let the
+        // count overflow if references are leaked.
+        if (*node.as_ptr()).count.fetch_add(1, AtomicOrdering::Relaxed) > isize::MAX as usize {
+            process::abort();
+        }
+    }
+
+    /// Removes a reference to the node, returning
+    /// the object to its pool if it was the last one.
+    ///
+    /// # Safety
+    /// The caller must hold a reference,
+    /// which it can't use afterwards.
+    pub(crate) unsafe fn release(node: NonNull<Node<T>>) {
+        if (*node.as_ptr()).count.fetch_sub(1, AtomicOrdering::Release) != 1 {
+            return;
+        }
+        // Synchronize with the other references' releases,
+        // so that their uses of the object happen before
+        // it is returned.
+        atomic::fence(AtomicOrdering::Acquire);
+
+        let contents = Node::take(node);
+        (*contents.pool).return_value(contents.value, contents.origin);
+        drop(contents.owner);
+    }
+
+    /// Takes the object out of the node if the caller
+    /// holds the only reference, which is consumed.
+    ///
+    /// # Safety
+    /// The caller must hold a reference.
+    pub(crate) unsafe fn try_unwrap(node: NonNull<Node<T>>) -> Option<Contents<T>> {
+        let count = &(*node.as_ptr()).count;
+        if count
+            .compare_exchange(1, 0, AtomicOrdering::Acquire, AtomicOrdering::Relaxed)
+            .is_err()
+        {
+            return None;
+        }
+
+        Some(Node::take(node))
+    }
+
+    /// Moves the contents out of a node whose count
+    /// just dropped to zero, and keeps the empty node
+    /// for reuse by the calling thread.
+    unsafe fn take(node: NonNull<Node<T>>) -> Contents<T> {
+        let mut node = Box::from_raw(node.as_ptr());
+        let contents = Contents {
+            value: node.value.assume_init_read(),
+            origin: node.origin.take(),
+            pool: node.pool,
+            owner: node.owner.take(),
+        };
+
+        let pool = &*contents.pool;
+        let mut nodes = pool.local().nodes.borrow_mut();
+        if nodes.len() < pool.settings.max_size {
+            nodes.push(node);
+        }
+        contents
+    }
+}
+
+/// The contents of a node which
+/// is no longer referenced.
+pub(crate) struct Contents<T>
+where
+    T: Recyclable,
+{
+    pub(crate) value: T,
+    pub(crate) origin: Option<Arc<Remote<T>>>,
+    /// Alive as long as `owner`, or as the
+    /// borrow of the pool by the node's owner.
+    pub(crate) pool: *const Pool<T>,
+    pub(crate) owner: Option<Arc<Pool<T>>>,
+}
+
+/// A reference-counted smart pointer to a pooled
+/// object, which returns the object to its pool
+/// once the last clone is dropped.
+///
+/// Like `Arc`, cloning only increments a counter,
+/// and the object can't be mutated while it is shared.
+/// The counter is stored together with the object in an
+/// allocation which is reused once the object returns to
+/// the pool, so retrieving a shared object usually doesn't
+/// allocate.
+///
+/// Like `OwnedRecycled`, this type keeps the pool
+/// alive and is not tied to a lifetime.
+///
+/// Objects of this type are obtained using `Pool::get_shared`,
+/// or by converting an `OwnedRecycled`.
+pub struct SharedRecycled<T>
+where
+    T: Recyclable,
+{
+    node: NonNull<Node<T>>,
+    _marker: PhantomData<T>,
+}
+
+impl<T> SharedRecycled<T>
+where
+    T: Recyclable,
+{
+    /// Returns the number of clones of
+    /// this pointer, including itself.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::Arc;
+    /// use swimmer::{Pool, SharedRecycled};
+    /// let pool: Arc<Pool<String>> = Arc::new(Pool::new());
+    ///
+    /// let string = pool.get_shared();
+    /// let clone = string.clone();
+    /// assert_eq!(SharedRecycled::strong_count(&string), 2);
+    /// drop(clone);
+    /// assert_eq!(SharedRecycled::strong_count(&string), 1);
+    /// ```
+    pub fn strong_count(this: &Self) -> usize {
+        unsafe {
+            // Safe because `this` holds a reference.
+            Node::count(this.node)
+        }
+    }
+
+    /// Returns whether both pointers point
+    /// to the same object.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        this.node == other.node
+    }
+
+    /// Returns the contained object as an `OwnedRecycled`
+    /// if `this` is the only pointer to it, so that it can
+    /// be mutated again. Otherwise, `this` is returned.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::Arc;
+    /// use swimmer::{Pool, SharedRecycled};
+    /// let pool: Arc<Pool<String>> = Arc::new(Pool::new());
+    ///
+    /// let string = pool.get_shared();
+    /// let clone = string.clone();
+    /// let string = SharedRecycled::try_unwrap(string).unwrap_err();
+    ///
+    /// drop(clone);
+    /// let mut string = SharedRecycled::try_unwrap(string).unwrap();
+    /// string.push_str("test");
+    /// ```
+    pub fn try_unwrap(this: Self) -> Result<OwnedRecycled<T>, Self> {
+        let contents = unsafe {
+            // Safe because `this` holds a reference,
+            // which is forgotten if it is consumed.
+            Node::try_unwrap(this.node)
+        };
+
+        match contents {
+            Some(contents) => {
+                mem::forget(this);
+                let pool = contents
+                    .owner
+                    .expect("shared objects keep their pool alive");
+                Ok(OwnedRecycled::from_parts(
+                    contents.value,
+                    pool,
+                    contents.origin,
+                ))
+            }
+            None => Err(this),
+        }
+    }
+
+    /// Returns the pool which the object
+    /// will be returned to.
+    pub fn pool(this: &Self) -> &Arc<Pool<T>> {
+        unsafe {
+            // Safe because `this` holds a reference,
+            // and the owner is only taken once the
+            // last one is released.
+            (*this.node.as_ptr())
+                .owner
+                .as_ref()
+                .expect("shared objects keep their pool alive")
+        }
+    }
+}
+
+impl<T> From<OwnedRecycled<T>> for SharedRecycled<T>
+where
+    T: Recyclable,
+{
+    /// Shares an object, so that it is returned to
+    /// the pool once all clones of it are dropped.
+    fn from(value: OwnedRecycled<T>) -> Self {
+        let (value, pool, origin) = OwnedRecycled::into_parts(value);
+        let node = Node::with_owner(value, origin, pool);
+
+        SharedRecycled {
+            node,
+            _marker: PhantomData,
+        }
+    }
+}
+
+// Safe because the object is shared between threads
+// exactly like the value of an `Arc<T>`, and it is
+// returned to the pool by whichever thread drops the
+// last clone, which is fine since `T` is `Send`.
+unsafe impl<T> Send for SharedRecycled<T> where T: Recyclable + Sync {}
+
+unsafe impl<T> Sync for SharedRecycled<T> where T: Recyclable + Sync {}
+
+impl<T> Clone for SharedRecycled<T>
+where
+    T: Recyclable,
+{
+    fn clone(&self) -> Self {
+        unsafe {
+            // Safe because `self` holds a reference.
+            Node::acquire(self.node);
+        }
+
+        SharedRecycled {
+            node: self.node,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Drop for SharedRecycled<T>
+where
+    T: Recyclable,
+{
+    fn drop(&mut self) {
+        unsafe {
+            // Safe because `self` holds a reference
+            // and is never used again.
+            Node::release(self.node);
+        }
+    }
+}
+
+impl<T> AsRef<T> for SharedRecycled<T>
+where
+    T: Recyclable,
+{
+    fn as_ref(&self) -> &T {
+        self
+    }
+}
+
+impl<T> Deref for SharedRecycled<T>
+where
+    T: Recyclable,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe {
+            // Safe because `self` holds a reference
+            // for as long as the result is borrowed.
+            Node::value(self.node)
+        }
+    }
+}
+
+impl<T> Display for SharedRecycled<T>
+where
+    T: Recyclable + Display,
+{
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.as_ref())
+    }
+}
+
+impl<T> Debug for SharedRecycled<T>
+where
+    T: Recyclable + Debug,
+{
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{:?}", self.as_ref())
+    }
+}
+
+impl<T> PartialEq<T> for SharedRecycled<T>
+where
+    T: Recyclable + PartialEq,
+{
+    fn eq(&self, other: &T) -> bool {
+        self.as_ref().eq(other)
+    }
+}
+
+impl<T> PartialOrd<T> for SharedRecycled<T>
+where
+    T: Recyclable + PartialOrd,
+{
+    fn partial_cmp(&self, other: &T) -> Option<Ordering> {
+        self.as_ref().partial_cmp(other)
+    }
+}
@@ -0,0 +1,155 @@
+#[cfg(feature = "stats")]
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+/// An event counted by a pool's statistics.
+#[derive(Clone, Copy)]
+pub(crate) enum Event {
+    /// An object was retrieved from the pool.
+    Get,
+    /// A new object had to be initialized.
+    Miss,
+    /// An object was returned to the pool.
+    Return,
+    /// An object was attached to the pool.
+    Attach,
+    /// An object was detached from the pool.
+    Detach,
+    /// A returned object was dropped
+    /// because the pool was full.
+    Overflow,
+    /// An object was dropped because
+    /// it was idle for too long.
+    Evict,
+}
+
+#[cfg(feature = "stats")]
+const EVENTS: usize = 7;
+
+/// Event counters for a single thread's buffer.
+///
+/// When the `stats` feature is disabled, this
+/// type is zero-sized and recording events
+/// compiles to nothing.
+#[derive(Default)]
+pub(crate) struct Counters {
+    #[cfg(feature = "stats")]
+    counts: [AtomicU64; EVENTS],
+}
+
+impl Counters {
+    #[cfg(feature = "stats")]
+    pub(crate) fn record(&self, event: Event) {
+        self.record_many(event, 1);
+    }
+
+    #[cfg(not(feature = "stats"))]
+    pub(crate) fn record(&self, _event: Event) {}
+
+    #[cfg(feature = "stats")]
+    pub(crate) fn record_many(&self, event: Event, count: usize) {
+        // Each thread only updates its own counters, so
+        // they don't need an atomic read-modify-write,
+        // which would be much slower.
+        let counter = &self.counts[event as usize];
+        counter.store(
+            counter.load(Ordering::Relaxed) + count as u64,
+            Ordering::Relaxed,
+        );
+    }
+
+    #[cfg(not(feature = "stats"))]
+    pub(crate) fn record_many(&self, _event: Event, _count: usize) {}
+
+    #[cfg(feature = "stats")]
+    pub(crate) fn count(&self, event: Event) -> u64 {
+        self.counts[event as usize].load(Ordering::Relaxed)
+    }
+}
+
+/// The largest size a pool has reached.
+#[derive(Default)]
+pub(crate) struct PeakSize {
+    #[cfg(feature = "stats")]
+    peak: AtomicUsize,
+}
+
+impl PeakSize {
+    #[cfg(feature = "stats")]
+    pub(crate) fn update(&self, size: usize) {
+        // The peak rarely changes, so check it first
+        // rather than writing to it on every return.
+        if size > self.peak.load(Ordering::Relaxed) {
+            self.peak.fetch_max(size, Ordering::Relaxed);
+        }
+    }
+
+    #[cfg(not(feature = "stats"))]
+    pub(crate) fn update(&self, _size: usize) {}
+
+    #[cfg(feature = "stats")]
+    pub(crate) fn get(&self) -> usize {
+        self.peak.load(Ordering::Relaxed)
+    }
+}
+
+/// A snapshot of a pool's statistics,
+/// obtained using `Pool::stats`.
+///
+/// The counters are aggregated across all
+/// threads which have used the pool. Because
+/// they are read without synchronizing with
+/// those threads, a snapshot taken while other
+/// threads use the pool may be slightly inconsistent.
+///
+/// This type is only available with the `stats` feature.
+#[cfg(feature = "stats")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    /// The number of objects retrieved from the pool,
+    /// including newly initialized objects.
+    pub gets: u64,
+    /// The number of retrievals which had to
+    /// initialize a new object because the
+    /// pool was empty.
+    pub misses: u64,
+    /// The number of objects returned to the pool,
+    /// including objects which were then dropped.
+    pub returns: u64,
+    /// The number of objects attached to the pool.
+    pub attaches: u64,
+    /// The number of objects detached from the pool.
+    pub detaches: u64,
+    /// The number of returned objects which were dropped
+    /// because they failed validation.
+    pub rejected: u64,
+    /// The number of returned objects which were dropped
+    /// because the pool was full.
+    pub overflowed: u64,
+    /// The number of objects which were dropped
+    /// because they were idle for too long.
+    pub evicted: u64,
+    /// The current number of objects in the pool.
+    pub size: usize,
+    /// The largest number of objects
+    /// the pool has held at once.
+    pub peak_size: usize,
+    /// The number of objects which have been
+    /// retrieved or attached and not yet returned
+    /// or detached.
+    pub checked_out: u64,
+}
+
+#[cfg(feature = "stats")]
+impl PoolStats {
+    /// Returns the fraction of retrievals which
+    /// reused an existing object, between 0 and 1.
+    ///
+    /// Returns 1 if no objects have been retrieved.
+    pub fn hit_rate(&self) -> f64 {
+        if self.gets == 0 {
+            1.0
+        } else {
+            (self.gets - self.misses) as f64 / self.gets as f64
+        }
+    }
+}
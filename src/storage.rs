@@ -0,0 +1,385 @@
+use crate::{Idle, ShardKey};
+use crossbeam_queue::SegQueue;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::mem;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+use std::vec::Drain;
+
+/// The strategy a pool uses to store
+/// objects which aren't checked out.
+///
+/// Set using `PoolBuilder::with_storage`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Storage {
+    /// Each thread has its own buffer, backed by
+    /// a global buffer shared between all threads.
+    /// See the crate-level documentation.
+    ///
+    /// This is the default, and it is the fastest
+    /// strategy for pools used by a fixed set of threads.
+    #[default]
+    ThreadLocal,
+    /// All threads share a single lock-free queue.
+    ///
+    /// Retrieving an object is slower than with
+    /// thread-local buffers, but objects are never held
+    /// by a particular thread, so this strategy works well
+    /// for pools used by many short-lived threads.
+    Global,
+    /// Objects are split between the given number of
+    /// shards, each protected by a mutex.
+    ///
+    /// Threads use the shard chosen by a hash of their
+    /// ID, or by the closure set using `PoolBuilder::with_shard_key`.
+    /// If a thread's shard is empty, objects are taken from the
+    /// neighbouring shards before initializing a new object.
+    ///
+    /// This suits thread-per-core runtimes, where tasks
+    /// move between a fixed number of worker threads, with
+    /// one shard per worker. At least one shard is always used.
+    Sharded(usize),
+}
+
+/// Where objects are returned to when they are
+/// dropped on a different thread than the one
+/// which retrieved them.
+///
+/// This only applies to pools using `Storage::ThreadLocal`.
+/// Set using `PoolBuilder::with_return_policy`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ReturnPolicy {
+    /// Objects are returned to the buffer of the
+    /// thread which drops them. This is the default.
+    #[default]
+    Current,
+    /// Objects are returned to the thread which
+    /// retrieved them, through a list of objects
+    /// returned by other threads. The thread takes
+    /// objects from this list once its own buffer
+    /// is empty.
+    ///
+    /// Objects retrieved by a thread which has
+    /// since exited are returned to the global buffer.
+    Origin,
+    /// Objects are returned to the global buffer,
+    /// where any thread can retrieve them.
+    Global,
+}
+
+impl Storage {
+    /// Returns whether threads keep objects in
+    /// their own buffers with this strategy.
+    pub(crate) fn is_thread_local(self) -> bool {
+        self == Storage::ThreadLocal
+    }
+}
+
+/// A buffer of objects shared between all threads.
+pub(crate) trait Store<T> {
+    fn push(&self, value: Idle<T>);
+
+    fn extend(&self, values: Drain<'_, Idle<T>>);
+
+    fn pop(&self) -> Option<Idle<T>>;
+
+    /// Moves up to `count` objects to the end of `values`.
+    fn pop_into(&self, values: &mut Vec<Idle<T>>, count: usize);
+
+    /// Removes up to `count` objects, preferring
+    /// the least recently returned ones.
+    fn remove(&self, count: usize) -> Vec<Idle<T>>;
+
+    /// Removes the objects which have been idle
+    /// for at least `timeout`, returning them.
+    fn take_expired(&self, timeout: Duration, now: Instant) -> Vec<Idle<T>>;
+
+    fn len(&self) -> usize;
+
+    /// Removes all objects.
+    fn take_all(&mut self) -> Vec<Idle<T>>;
+}
+
+/// A `Vec` protected by a mutex, used as the
+/// global buffer of `Storage::ThreadLocal`.
+///
+/// Objects are pushed to and popped from
+/// the back of the vector, so the front holds
+/// the least recently returned objects.
+pub(crate) struct Locked<T>(Mutex<Vec<Idle<T>>>);
+
+impl<T> Locked<T> {
+    fn new() -> Locked<T> {
+        Locked(Mutex::new(vec![]))
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<Idle<T>>> {
+        // The buffer is never left in an inconsistent
+        // state, so it is fine to ignore poisoning.
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T> Store<T> for Locked<T> {
+    fn push(&self, value: Idle<T>) {
+        self.lock().push(value);
+    }
+
+    fn extend(&self, values: Drain<'_, Idle<T>>) {
+        self.lock().extend(values);
+    }
+
+    fn pop(&self) -> Option<Idle<T>> {
+        self.lock().pop()
+    }
+
+    fn pop_into(&self, values: &mut Vec<Idle<T>>, count: usize) {
+        let mut buffer = self.lock();
+        let start = buffer.len() - count.min(buffer.len());
+        values.extend(buffer.drain(start..));
+    }
+
+    fn remove(&self, count: usize) -> Vec<Idle<T>> {
+        let mut buffer = self.lock();
+        let count = count.min(buffer.len());
+        buffer.drain(..count).collect()
+    }
+
+    fn take_expired(&self, timeout: Duration, now: Instant) -> Vec<Idle<T>> {
+        take_expired(&mut self.lock(), timeout, now)
+    }
+
+    fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    fn take_all(&mut self) -> Vec<Idle<T>> {
+        mem::take(self.0.get_mut().unwrap_or_else(PoisonError::into_inner))
+    }
+}
+
+/// A lock-free queue, used by `Storage::Global`.
+///
+/// Objects are popped in the order they were
+/// pushed, so the least recently returned
+/// objects are reused first.
+pub(crate) struct LockFree<T>(SegQueue<Idle<T>>);
+
+impl<T> Store<T> for LockFree<T> {
+    fn push(&self, value: Idle<T>) {
+        self.0.push(value);
+    }
+
+    fn extend(&self, values: Drain<'_, Idle<T>>) {
+        for value in values {
+            self.0.push(value);
+        }
+    }
+
+    fn pop(&self) -> Option<Idle<T>> {
+        self.0.pop()
+    }
+
+    fn pop_into(&self, values: &mut Vec<Idle<T>>, count: usize) {
+        values.extend((0..count).map_while(|_| self.0.pop()));
+    }
+
+    fn remove(&self, count: usize) -> Vec<Idle<T>> {
+        (0..count).map_while(|_| self.0.pop()).collect()
+    }
+
+    fn take_expired(&self, timeout: Duration, now: Instant) -> Vec<Idle<T>> {
+        // Objects popped here are briefly invisible
+        // to other threads, which then initialize
+        // new objects or find others instead.
+        let mut values = self.remove(self.0.len());
+        let expired = take_expired(&mut values, timeout, now);
+        self.extend(values.drain(..));
+        expired
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn take_all(&mut self) -> Vec<Idle<T>> {
+        self.remove(self.0.len())
+    }
+}
+
+thread_local! {
+    /// The default shard key of the current thread.
+    static THREAD_KEY: usize = {
+        let mut hasher = DefaultHasher::new();
+        thread::current().id().hash(&mut hasher);
+        hasher.finish() as usize
+    };
+}
+
+/// Mutex-protected shards, used by `Storage::Sharded`.
+pub(crate) struct Sharded<T> {
+    shards: Box<[Locked<T>]>,
+    key: Option<Box<ShardKey>>,
+}
+
+impl<T> Sharded<T> {
+    fn new(shards: usize, key: Option<Box<ShardKey>>) -> Sharded<T> {
+        Sharded {
+            shards: (0..shards.max(1)).map(|_| Locked::new()).collect(),
+            key,
+        }
+    }
+
+    /// Returns the shards in the order the calling
+    /// thread should use them: first its own shard,
+    /// then the following ones.
+    fn shards(&self) -> impl Iterator<Item = &Locked<T>> + Clone {
+        let key = if let Some(key) = self.key.as_ref() {
+            key()
+        } else {
+            THREAD_KEY.with(|key| *key)
+        };
+
+        let (before, after) = self.shards.split_at(key % self.shards.len());
+        after.iter().chain(before)
+    }
+
+    fn own_shard(&self) -> &Locked<T> {
+        self.shards().next().unwrap()
+    }
+}
+
+impl<T> Store<T> for Sharded<T> {
+    fn push(&self, value: Idle<T>) {
+        self.own_shard().push(value);
+    }
+
+    fn extend(&self, values: Drain<'_, Idle<T>>) {
+        // Spread the objects across all shards.
+        for (value, shard) in values.zip(self.shards().cycle()) {
+            shard.push(value);
+        }
+    }
+
+    fn pop(&self) -> Option<Idle<T>> {
+        self.shards().find_map(Locked::pop)
+    }
+
+    fn pop_into(&self, values: &mut Vec<Idle<T>>, count: usize) {
+        let target = values.len() + count;
+        for shard in self.shards() {
+            if values.len() >= target {
+                break;
+            }
+            shard.pop_into(values, target - values.len());
+        }
+    }
+
+    fn remove(&self, count: usize) -> Vec<Idle<T>> {
+        let mut removed = vec![];
+        for shard in self.shards() {
+            if removed.len() >= count {
+                break;
+            }
+            removed.extend(shard.remove(count - removed.len()));
+        }
+        removed
+    }
+
+    fn take_expired(&self, timeout: Duration, now: Instant) -> Vec<Idle<T>> {
+        self.shards
+            .iter()
+            .flat_map(|shard| shard.take_expired(timeout, now))
+            .collect()
+    }
+
+    fn len(&self) -> usize {
+        self.shards.iter().map(Locked::len).sum()
+    }
+
+    fn take_all(&mut self) -> Vec<Idle<T>> {
+        self.shards.iter_mut().flat_map(Locked::take_all).collect()
+    }
+}
+
+/// The global buffer of a pool, using
+/// the backend selected by its `Storage`.
+pub(crate) enum Shared<T> {
+    Locked(Locked<T>),
+    // Boxed since the queue is much larger than a mutex.
+    LockFree(Box<LockFree<T>>),
+    Sharded(Sharded<T>),
+}
+
+impl<T> Shared<T> {
+    /// Creates the backend for `storage`. `key` is
+    /// only used with `Storage::Sharded`.
+    pub(crate) fn new(storage: Storage, key: Option<Box<ShardKey>>) -> Shared<T> {
+        match storage {
+            Storage::ThreadLocal => Shared::Locked(Locked::new()),
+            Storage::Global => Shared::LockFree(Box::new(LockFree(SegQueue::new()))),
+            Storage::Sharded(shards) => Shared::Sharded(Sharded::new(shards, key)),
+        }
+    }
+}
+
+/// Calls a method on whichever backend `$shared` uses.
+macro_rules! dispatch {
+    ($shared:expr, $store:ident => $call:expr) => {
+        match $shared {
+            Shared::Locked($store) => $call,
+            Shared::LockFree($store) => $call,
+            Shared::Sharded($store) => $call,
+        }
+    };
+}
+
+impl<T> Store<T> for Shared<T> {
+    fn push(&self, value: Idle<T>) {
+        dispatch!(self, store => store.push(value))
+    }
+
+    fn extend(&self, values: Drain<'_, Idle<T>>) {
+        dispatch!(self, store => store.extend(values))
+    }
+
+    fn pop(&self) -> Option<Idle<T>> {
+        dispatch!(self, store => store.pop())
+    }
+
+    fn pop_into(&self, values: &mut Vec<Idle<T>>, count: usize) {
+        dispatch!(self, store => store.pop_into(values, count))
+    }
+
+    fn remove(&self, count: usize) -> Vec<Idle<T>> {
+        dispatch!(self, store => store.remove(count))
+    }
+
+    fn take_expired(&self, timeout: Duration, now: Instant) -> Vec<Idle<T>> {
+        dispatch!(self, store => store.take_expired(timeout, now))
+    }
+
+    fn len(&self) -> usize {
+        dispatch!(self, store => store.len())
+    }
+
+    fn take_all(&mut self) -> Vec<Idle<T>> {
+        dispatch!(self, store => store.take_all())
+    }
+}
+
+/// Removes the objects in `values` which have been
+/// idle for at least `timeout`, returning them.
+pub(crate) fn take_expired<T>(
+    values: &mut Vec<Idle<T>>,
+    timeout: Duration,
+    now: Instant,
+) -> Vec<Idle<T>> {
+    let (expired, remaining) = values
+        .drain(..)
+        .partition(|idle| idle.is_expired(timeout, now));
+    *values = remaining;
+    expired
+}
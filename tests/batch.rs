@@ -0,0 +1,69 @@
+//! Tests for retrieving and returning objects in batches.
+
+use swimmer::{Pool, Storage};
+
+#[test]
+fn get_many_reuses_objects() {
+    let pool: Pool<Vec<u8>> = swimmer::builder()
+        .with_starting_size(48)
+        .with_max_size(32)
+        .build();
+
+    let mut buffers = pool.get_many(64);
+    assert_eq!(buffers.len(), 64);
+    assert_eq!(pool.size(), 0);
+
+    for buffer in &mut buffers {
+        buffer.push(1);
+    }
+    pool.recycle_all(buffers);
+
+    // Half of the maximum size stays in the local buffer,
+    // the rest is moved to the global buffer as far as
+    // it has room, and the surplus is dropped.
+    assert_eq!(pool.size(), 48);
+    assert_eq!(pool.local_size(), 16);
+    assert_eq!(pool.global_size(), 32);
+    assert!(pool.get_many(48).iter().all(|buffer| buffer.is_empty()));
+}
+
+#[test]
+fn recycle_all_respects_limits() {
+    let pool: Pool<String> = swimmer::builder()
+        .with_max_total_size(4)
+        .with_validator(|string: &String| string.capacity() < 64)
+        .build();
+    let other: Pool<String> = Pool::new();
+
+    let mut strings = pool.get_many(6);
+    strings[0].reserve(128);
+    strings.push(other.get());
+    pool.recycle_all(strings);
+
+    assert_eq!(pool.size(), 4);
+    assert_eq!(pool.rejected(), 1);
+    assert_eq!(other.size(), 1);
+}
+
+#[test]
+fn extend_with_global_storage() {
+    let pool: Pool<Vec<u8>> = swimmer::builder()
+        .with_storage(Storage::Global)
+        .with_max_total_size(8)
+        .build();
+
+    pool.extend((0..10).map(|_| Vec::with_capacity(16)));
+    assert_eq!(pool.size(), 8);
+
+    let buffers = pool.get_many(10);
+    assert_eq!(
+        buffers
+            .iter()
+            .filter(|buffer| buffer.capacity() == 16)
+            .count(),
+        8
+    );
+
+    pool.recycle_all(buffers);
+    assert_eq!(pool.global_size(), 8);
+}
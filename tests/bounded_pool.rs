@@ -0,0 +1,239 @@
+//! Tests for waiting on a `BoundedPool`.
+
+mod common;
+
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+use std::time::Duration;
+use swimmer::BoundedPool;
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = Box::pin(future);
+
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+fn counting_pool(capacity: usize) -> (Arc<BoundedPool<Vec<u8>>>, Arc<AtomicUsize>) {
+    let (builder, created) = common::counting_builder();
+    (Arc::new(builder.build_bounded(capacity)), created)
+}
+
+#[test]
+fn blocking_get_waits_for_return() {
+    let (pool, created) = counting_pool(2);
+
+    let handles: Vec<_> = (0..8)
+        .map(|_| {
+            let pool = Arc::clone(&pool);
+            thread::spawn(move || {
+                for _ in 0..100 {
+                    let mut value = pool.get();
+                    value.push(1);
+                    assert_eq!(value.len(), 1);
+                }
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    assert!(created.load(Ordering::SeqCst) <= 2);
+    assert_eq!(pool.available(), 2);
+}
+
+#[test]
+fn get_timeout_expires() {
+    let (pool, _) = counting_pool(1);
+
+    let value = pool.get();
+    assert!(pool.get_timeout(Duration::from_millis(10)).is_none());
+    drop(value);
+    assert!(pool.get_timeout(Duration::from_millis(10)).is_some());
+}
+
+#[test]
+fn async_get_waits_for_return() {
+    let (pool, created) = counting_pool(1);
+
+    let value = pool.get();
+    let waiter = {
+        let pool = Arc::clone(&pool);
+        thread::spawn(move || {
+            let value = block_on(pool.get_async());
+            value.len()
+        })
+    };
+
+    thread::sleep(Duration::from_millis(10));
+    drop(value);
+
+    assert_eq!(waiter.join().unwrap(), 0);
+    assert_eq!(created.load(Ordering::SeqCst), 1);
+}
+
+struct CountingWaker(AtomicUsize);
+
+impl Wake for CountingWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn dropped_checkout_passes_on_wakeup() {
+    let (pool, _) = counting_pool(1);
+
+    let value = pool.get();
+    let first_waker = Arc::new(CountingWaker(AtomicUsize::new(0)));
+    let second_waker = Arc::new(CountingWaker(AtomicUsize::new(0)));
+
+    let mut first = Box::pin(pool.get_async());
+    let mut second = Box::pin(pool.get_async());
+    let first_waker_ref = Waker::from(Arc::clone(&first_waker));
+    let second_waker_ref = Waker::from(Arc::clone(&second_waker));
+    assert!(first
+        .as_mut()
+        .poll(&mut Context::from_waker(&first_waker_ref))
+        .is_pending());
+    assert!(second
+        .as_mut()
+        .poll(&mut Context::from_waker(&second_waker_ref))
+        .is_pending());
+
+    // Wakes `first`, which is then dropped
+    drop(value);
+    assert_eq!(first_waker.0.load(Ordering::SeqCst), 1);
+    assert_eq!(second_waker.0.load(Ordering::SeqCst), 0);
+    drop(first);
+
+    assert_eq!(second_waker.0.load(Ordering::SeqCst), 1);
+    assert!(second
+        .as_mut()
+        .poll(&mut Context::from_waker(&second_waker_ref))
+        .is_ready());
+}
+
+#[test]
+fn repolled_checkout_is_woken_once() {
+    let (pool, _) = counting_pool(1);
+
+    let value = pool.get();
+    let first_waker = Arc::new(CountingWaker(AtomicUsize::new(0)));
+    let second_waker = Arc::new(CountingWaker(AtomicUsize::new(0)));
+    let first_waker_ref = Waker::from(Arc::clone(&first_waker));
+    let second_waker_ref = Waker::from(Arc::clone(&second_waker));
+
+    let mut first = Box::pin(pool.get_async());
+    let mut second = Box::pin(pool.get_async());
+    for _ in 0..2 {
+        assert!(first
+            .as_mut()
+            .poll(&mut Context::from_waker(&first_waker_ref))
+            .is_pending());
+    }
+    assert!(second
+        .as_mut()
+        .poll(&mut Context::from_waker(&second_waker_ref))
+        .is_pending());
+
+    drop(value);
+    assert_eq!(first_waker.0.load(Ordering::SeqCst), 1);
+    let value = match first
+        .as_mut()
+        .poll(&mut Context::from_waker(&first_waker_ref))
+    {
+        Poll::Ready(value) => value,
+        Poll::Pending => panic!("the first checkout should be ready"),
+    };
+
+    // The second release goes to `second`,
+    // not to a stale waker of `first`
+    drop(value);
+    assert_eq!(first_waker.0.load(Ordering::SeqCst), 1);
+    assert_eq!(second_waker.0.load(Ordering::SeqCst), 1);
+    assert!(second
+        .as_mut()
+        .poll(&mut Context::from_waker(&second_waker_ref))
+        .is_ready());
+}
+
+#[test]
+fn checkout_cancelled_while_exhausted_is_not_woken() {
+    let (pool, _) = counting_pool(1);
+
+    let value = pool.get();
+    let first_waker = Arc::new(CountingWaker(AtomicUsize::new(0)));
+    let second_waker = Arc::new(CountingWaker(AtomicUsize::new(0)));
+    let first_waker_ref = Waker::from(Arc::clone(&first_waker));
+    let second_waker_ref = Waker::from(Arc::clone(&second_waker));
+
+    let mut first = Box::pin(pool.get_async());
+    let mut second = Box::pin(pool.get_async());
+    assert!(first
+        .as_mut()
+        .poll(&mut Context::from_waker(&first_waker_ref))
+        .is_pending());
+    assert!(second
+        .as_mut()
+        .poll(&mut Context::from_waker(&second_waker_ref))
+        .is_pending());
+
+    drop(first);
+    drop(value);
+    assert_eq!(first_waker.0.load(Ordering::SeqCst), 0);
+    assert_eq!(second_waker.0.load(Ordering::SeqCst), 1);
+    assert!(second
+        .as_mut()
+        .poll(&mut Context::from_waker(&second_waker_ref))
+        .is_ready());
+}
+
+#[test]
+fn failed_checkout_releases_slot() {
+    let pool: BoundedPool<String> = swimmer::builder()
+        .with_try_supplier(|| Err("no connection"))
+        .build_bounded(1);
+
+    assert!(pool.try_get().is_err());
+    assert_eq!(pool.available(), 1);
+}
+
+#[test]
+fn panicking_supplier_releases_slot() {
+    let pool: BoundedPool<String> = swimmer::builder()
+        .with_supplier(|| panic!("no connection"))
+        .build_bounded(1);
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        pool.get();
+    }));
+    assert!(result.is_err());
+    assert_eq!(pool.available(), 1);
+}
+
+#[test]
+#[should_panic(expected = "capacity must be nonzero")]
+fn zero_capacity_is_rejected() {
+    let _pool: BoundedPool<String> = BoundedPool::new(0);
+}
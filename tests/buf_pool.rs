@@ -0,0 +1,94 @@
+//! Tests for `BufPool`.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use swimmer::BufPool;
+
+#[test]
+fn buffers_are_sorted_by_capacity() {
+    let pool = BufPool::new();
+
+    let small = pool.get(100);
+    let large = pool.get(5000);
+    assert_eq!(small.capacity(), 128);
+    assert_eq!(large.capacity(), 8192);
+    drop(small);
+    drop(large);
+
+    assert_eq!(pool.bucket(128).size(), 1);
+    assert_eq!(pool.bucket(8192).size(), 1);
+    assert_eq!(pool.size(), 2);
+
+    // A small request never receives the large buffer
+    assert_eq!(pool.get(1).capacity(), 1);
+    assert_eq!(pool.get(128).capacity(), 128);
+}
+
+#[test]
+fn grown_buffers_change_bucket() {
+    let pool = BufPool::new();
+
+    let mut buf = pool.get(16);
+    buf.extend_from_slice(&[0; 100]);
+    let capacity = buf.capacity();
+    drop(buf);
+
+    assert_eq!(pool.bucket(16).size(), 0);
+    assert_eq!(pool.size(), 1);
+
+    let buf = pool.get(64);
+    assert_eq!(buf.capacity(), capacity);
+    assert!(buf.is_empty());
+}
+
+#[test]
+fn buffers_without_capacity_are_dropped() {
+    let dropped = Arc::new(AtomicUsize::new(0));
+    let pool = {
+        let dropped = Arc::clone(&dropped);
+        BufPool::with_builder(move |_| {
+            let dropped = Arc::clone(&dropped);
+            swimmer::builder().with_drop_hook(move |_| {
+                dropped.fetch_add(1, Ordering::SeqCst);
+            })
+        })
+    };
+
+    let mut buf = pool.get(0);
+    assert!(buf.capacity() >= 1);
+    *buf = Vec::new();
+    drop(buf);
+
+    assert_eq!(pool.size(), 0);
+    assert_eq!(dropped.load(Ordering::SeqCst), 1);
+    #[cfg(feature = "stats")]
+    assert_eq!(pool.bucket(1).stats().checked_out, 0);
+}
+
+#[test]
+fn recyclers_keep_capacity() {
+    let pool = BufPool::with_builder(|_| {
+        swimmer::builder().with_recycler(|buf: &mut Vec<u8>| {
+            buf.clear();
+            buf.shrink_to_fit();
+        })
+    });
+
+    drop(pool.get(1000));
+    assert!(pool.get(1000).capacity() >= 1000);
+}
+
+#[test]
+fn buckets_use_configured_builders() {
+    let pool = BufPool::with_builder(|capacity| {
+        swimmer::builder()
+            .with_starting_size(if capacity == 64 { 2 } else { 0 })
+            .with_max_total_size(2)
+    });
+
+    let bufs: Vec<_> = (0..4).map(|_| pool.get(64)).collect();
+    assert!(bufs.iter().all(|buf| buf.capacity() == 64));
+    drop(bufs);
+
+    assert_eq!(pool.bucket(64).size(), 2);
+}
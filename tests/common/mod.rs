@@ -0,0 +1,24 @@
+//! Helpers shared by the integration tests.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use swimmer::{PoolBuilder, Recyclable};
+
+/// Returns a builder whose supplier counts
+/// the objects it initializes, along with
+/// the counter.
+pub fn counting_builder<T>() -> (PoolBuilder<T>, Arc<AtomicUsize>)
+where
+    T: Recyclable,
+{
+    let created = Arc::new(AtomicUsize::new(0));
+    let builder = {
+        let created = Arc::clone(&created);
+        swimmer::builder().with_supplier(move || {
+            created.fetch_add(1, Ordering::SeqCst);
+            T::new()
+        })
+    };
+
+    (builder, created)
+}
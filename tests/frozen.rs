@@ -0,0 +1,73 @@
+//! Tests for immutable views into pooled buffers.
+
+use std::sync::Arc;
+use std::thread;
+use swimmer::{FrozenRecycled, OwnedRecycled, Pool, Recycled};
+
+#[test]
+fn returned_after_last_view() {
+    let pool: Pool<Vec<u8>> = Pool::new();
+
+    let mut buf = pool.get();
+    buf.extend_from_slice(b"0123456789");
+    let mut buf = Recycled::freeze(buf);
+
+    let back = buf.split_off(6);
+    let front = buf.split_to(2);
+    let middle = buf.slice(1..3);
+    assert_eq!(&front[..], b"01");
+    assert_eq!(&buf[..], b"2345");
+    assert_eq!(&middle[..], b"34");
+    assert_eq!(&back[..], b"6789");
+    assert_eq!(buf.slice(..), buf);
+
+    let views = vec![front, buf, middle];
+    drop(views);
+    assert_eq!(pool.size(), 0);
+    drop(back);
+    assert_eq!(pool.size(), 1);
+    assert!(pool.get().capacity() >= 10);
+}
+
+#[test]
+fn empty_views() {
+    let pool: Pool<Vec<u8>> = Pool::new();
+
+    let mut buf = Recycled::freeze(pool.get());
+    assert!(buf.is_empty());
+    assert!(buf.split_to(0).is_empty());
+    assert_eq!(&buf[..], b"");
+}
+
+#[test]
+#[should_panic]
+fn slice_out_of_bounds() {
+    let pool: Pool<Vec<u8>> = Pool::new();
+
+    let mut buf = pool.get();
+    buf.push(1);
+    Recycled::freeze(buf).slice(..2);
+}
+
+#[test]
+fn views_across_threads() {
+    let pool: Arc<Pool<Vec<u8>>> = Arc::new(Pool::new());
+    pool.local_size();
+
+    let mut buf = pool.get_owned();
+    buf.extend(0..=255);
+    let buf: FrozenRecycled<'static> = OwnedRecycled::freeze(buf);
+
+    let handles: Vec<_> = (0..4)
+        .map(|i| {
+            let view = buf.slice(i * 64..(i + 1) * 64);
+            thread::spawn(move || view.iter().map(|&byte| byte as usize).sum::<usize>())
+        })
+        .collect();
+    drop(buf);
+    let sum: usize = handles.into_iter().map(|h| h.join().unwrap()).sum();
+    assert_eq!(sum, (0..=255).sum::<usize>());
+
+    // The last view was dropped by one of the threads.
+    assert_eq!(pool.size(), 1);
+}
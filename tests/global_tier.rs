@@ -0,0 +1,91 @@
+//! Test for sharing objects between threads through the global buffer.
+
+mod common;
+
+use std::sync::atomic::Ordering;
+use std::sync::{mpsc, Arc};
+use std::thread;
+use swimmer::Pool;
+
+#[test]
+fn consumer_returns_are_reused_by_producer() {
+    let (builder, created) = common::counting_builder();
+    let pool: Arc<Pool<Vec<u8>>> = Arc::new(builder.build());
+
+    let (tx, rx) = mpsc::channel();
+    let consumer = thread::spawn(move || {
+        for value in rx {
+            drop(value);
+        }
+    });
+
+    // Retrieve all objects before sending any, so that
+    // the producer can't reuse objects while the
+    // consumer is still running.
+    let values: Vec<_> = (0..1024).map(|_| pool.get_owned()).collect();
+    for value in values {
+        tx.send(value).unwrap();
+    }
+    drop(tx);
+    consumer.join().unwrap();
+    assert_eq!(created.load(Ordering::SeqCst), 1024);
+
+    // Objects returned by the consumer should now be
+    // available to this thread through the global buffer.
+    let values: Vec<_> = (0..512).map(|_| pool.get()).collect();
+    assert_eq!(created.load(Ordering::SeqCst), 1024);
+    drop(values);
+}
+
+#[test]
+fn max_total_size_drops_overflow() {
+    let pool: Pool<String> = swimmer::builder()
+        .with_max_size(4)
+        .with_max_total_size(8)
+        .build();
+
+    let values: Vec<_> = (0..32).map(|_| pool.get()).collect();
+    drop(values);
+
+    assert_eq!(pool.size(), 8);
+}
+
+#[test]
+fn max_size_bounds_global_buffer() {
+    let pool: Pool<String> = swimmer::builder().with_max_size(8).build();
+
+    let values: Vec<_> = (0..100).map(|_| pool.get()).collect();
+    drop(values);
+
+    // Both buffers are full, and the
+    // remaining objects were dropped.
+    assert_eq!(pool.local_size(), 8);
+    assert_eq!(pool.global_size(), 8);
+    assert_eq!(pool.size(), 16);
+}
+
+#[test]
+fn size_includes_other_threads() {
+    let pool: Arc<Pool<String>> = Arc::new(Pool::with_size(10));
+    assert_eq!(pool.local_size(), 0);
+
+    let other = {
+        let pool = Arc::clone(&pool);
+        thread::spawn(move || {
+            let values: Vec<_> = (0..20).map(|_| pool.get()).collect();
+            drop(values);
+            pool.local_size()
+        })
+    };
+    assert!(other.join().unwrap() > 0);
+
+    // The other thread's objects were moved
+    // to the global buffer when it exited.
+    assert_eq!(pool.size(), 20);
+    assert_eq!(pool.local_size(), 0);
+    assert_eq!(pool.global_size(), 20);
+
+    let sizes = pool.thread_sizes();
+    assert_eq!(sizes.len(), 2);
+    assert_eq!(sizes.iter().map(|(_, size)| size).sum::<usize>(), 0);
+}
@@ -0,0 +1,52 @@
+//! Tests for `KeyedPool`.
+
+use std::thread;
+use swimmer::KeyedPool;
+
+fn size_class(len: usize) -> usize {
+    len.next_power_of_two()
+}
+
+#[test]
+fn objects_are_returned_to_their_key() {
+    let pool: KeyedPool<usize, Vec<u8>> = KeyedPool::new(|&capacity: &usize| {
+        swimmer::builder().with_supplier(move || Vec::with_capacity(capacity))
+    });
+    assert!(pool.is_empty());
+
+    let small = pool.get(&size_class(10));
+    let large = pool.get(&size_class(3000));
+    assert!(small.capacity() >= 16);
+    assert!(large.capacity() >= 4096);
+    drop(small);
+    drop(large);
+
+    assert_eq!(pool.len(), 2);
+    assert_eq!(pool.size(), 2);
+    assert_eq!(pool.pool(&16).size(), 1);
+    assert_eq!(pool.pool(&4096).size(), 1);
+
+    // Still one object per key
+    assert!(pool.get(&16).capacity() < 4096);
+}
+
+#[test]
+fn pools_are_created_once_across_threads() {
+    let pool: KeyedPool<u8, String> =
+        KeyedPool::new(|_: &u8| swimmer::builder().with_starting_size(1));
+
+    thread::scope(|s| {
+        for _ in 0..8 {
+            s.spawn(|| {
+                for key in 0..16 {
+                    let mut string = pool.get(&key);
+                    string.push_str("test");
+                }
+            });
+        }
+    });
+
+    assert_eq!(pool.len(), 16);
+    assert!(pool.size() >= 16);
+    assert_eq!(*pool.get(&0), "");
+}
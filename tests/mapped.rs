@@ -0,0 +1,85 @@
+//! Tests for guards mapped to a part of a pooled object.
+
+use std::thread;
+use swimmer::{MappedRecycled, Pool, Recyclable, Recycled};
+
+#[derive(Default)]
+struct Frame {
+    header: [u8; 16],
+    data: Vec<u8>,
+}
+
+impl Recyclable for Frame {
+    fn new() -> Self {
+        Frame::default()
+    }
+
+    fn recycle(&mut self) {
+        self.header = [0; 16];
+        self.data.clear();
+    }
+}
+
+#[test]
+fn mapped_field_survives_moves() {
+    let pool: Pool<Frame> = Pool::new();
+
+    let mut header = Recycled::map(pool.get(), |frame| &mut frame.header);
+    header[0] = 1;
+
+    // Moving the guard moves the object along with it.
+    let mut boxed = Box::new(header);
+    boxed[1] = 2;
+    let header = *boxed;
+    assert_eq!(header[..2], [1, 2]);
+
+    let frame = MappedRecycled::into_inner(header);
+    assert_eq!(frame.header[..2], [1, 2]);
+    assert_eq!(pool.size(), 0);
+}
+
+#[test]
+fn whole_object_is_returned() {
+    let pool: Pool<Frame> = Pool::new();
+
+    let mut frame = pool.get();
+    frame.header[0] = 1;
+    let mut data = Recycled::map(frame, |frame| &mut frame.data);
+    data.extend_from_slice(b"hello");
+    assert!(std::ptr::eq(MappedRecycled::pool(&data), &pool));
+    drop(data);
+    assert_eq!(pool.size(), 1);
+
+    let frame = pool.get();
+    assert_eq!(frame.header, [0; 16]);
+    assert!(frame.data.is_empty());
+    assert!(frame.data.capacity() >= 5);
+}
+
+#[test]
+fn map_outside_the_object() {
+    let pool: Pool<Vec<u64>> = Pool::new();
+
+    let mut values = pool.get();
+    values.extend_from_slice(&[1, 2, 3]);
+
+    // The target lives in the vector's heap allocation.
+    let mut last = Recycled::map(values, |values| values.last_mut().unwrap());
+    *last += 1;
+    let moved = last;
+    assert_eq!(*moved, 4);
+
+    let values = MappedRecycled::into_inner(moved);
+    assert_eq!(values, [1, 2, 4]);
+}
+
+#[test]
+fn mapped_guard_across_threads() {
+    let pool: Pool<Frame> = Pool::new();
+
+    let mut data = Recycled::map(pool.get(), |frame| &mut frame.data);
+    thread::scope(|scope| {
+        scope.spawn(|| data.push(1));
+    });
+    assert_eq!(*data, [1]);
+}
@@ -0,0 +1,221 @@
+//! Tests exercising the unsafe parts of the crate
+//! with types which have validity invariants.
+//!
+//! These are intended to be run under Miri
+//! using `cargo +nightly miri test`.
+
+use std::ptr::NonNull;
+use std::sync::Arc;
+use std::thread;
+use swimmer::{
+    KeyedPool, MappedRecycled, OwnedRecycled, Pool, Recyclable, Recycled, SharedRecycled,
+};
+
+static NAMES: [&str; 2] = ["first", "second"];
+
+enum State {
+    Idle,
+    Busy(Box<u32>),
+}
+
+struct Invariants {
+    name: &'static str,
+    ptr: NonNull<u32>,
+    boxed: Box<u32>,
+    state: State,
+}
+
+// Safe because `ptr` always points to a `static`.
+unsafe impl Send for Invariants {}
+unsafe impl Sync for Invariants {}
+
+static VALUE: u32 = 42;
+
+impl Recyclable for Invariants {
+    fn new() -> Self {
+        Self {
+            name: NAMES[0],
+            ptr: NonNull::from(&VALUE),
+            boxed: Box::new(0),
+            state: State::Idle,
+        }
+    }
+
+    fn recycle(&mut self) {
+        self.name = NAMES[0];
+        *self.boxed = 0;
+        self.state = State::Idle;
+    }
+}
+
+#[test]
+fn get_and_drop() {
+    let pool: Pool<Invariants> = Pool::with_size(2);
+
+    let mut value = pool.get();
+    value.name = NAMES[1];
+    value.state = State::Busy(Box::new(1));
+    *value.boxed = 5;
+    if let State::Busy(ref busy) = value.state {
+        assert_eq!(**busy, 1);
+    }
+    drop(value);
+
+    let value = pool.get();
+    assert_eq!(value.name, NAMES[0]);
+    assert_eq!(*value.boxed, 0);
+    assert_eq!(unsafe { *value.ptr.as_ref() }, 42);
+    assert!(match value.state {
+        State::Idle => true,
+        State::Busy(_) => false,
+    });
+}
+
+#[test]
+fn attach_and_drop() {
+    let pool: Pool<Invariants> = Pool::new();
+
+    let value = pool.attach(Invariants::new());
+    drop(value);
+
+    assert_eq!(pool.size(), 1);
+}
+
+#[test]
+fn detach() {
+    let pool: Pool<Invariants> = Pool::with_size(1);
+
+    let value = pool.detached();
+    assert_eq!(pool.size(), 0);
+    drop(value);
+    assert_eq!(pool.size(), 0);
+}
+
+#[test]
+fn owned_across_threads() {
+    let pool: Arc<Pool<Vec<Box<u8>>>> = Arc::new(Pool::new());
+
+    let mut value = pool.get_owned();
+    value.push(Box::new(1));
+
+    thread::spawn(move || {
+        value.push(Box::new(2));
+        drop(value);
+    })
+    .join()
+    .unwrap();
+
+    assert_eq!(pool.size(), 1);
+    assert!(pool.get().is_empty());
+}
+
+#[test]
+fn drop_pool_with_values() {
+    let pool: Pool<Vec<Box<u64>>> = swimmer::builder()
+        .with_supplier(|| vec![Box::new(7)])
+        .with_starting_size(4)
+        .build();
+
+    let values: Vec<_> = (0..8).map(|_| pool.get()).collect();
+    drop(values);
+    drop(pool);
+}
+
+#[test]
+fn into_inner() {
+    let pool: Pool<Invariants> = Pool::with_size(1);
+
+    let value = Recycled::into_inner(pool.get());
+    assert_eq!(pool.size(), 0);
+    drop(value);
+
+    let pool = Arc::new(pool);
+    let value = OwnedRecycled::into_inner(pool.get_owned());
+    assert_eq!(Arc::strong_count(&pool), 1);
+    drop(value);
+}
+
+#[test]
+fn keyed_pool_across_threads() {
+    let pool: KeyedPool<usize, Vec<Box<u64>>> = KeyedPool::new(|&len: &usize| {
+        swimmer::builder().with_supplier(move || (0..len as u64).map(Box::new).collect())
+    });
+
+    thread::scope(|s| {
+        for len in 0..4 {
+            let pool = &pool;
+            s.spawn(move || {
+                let first = pool.get(&len);
+                let second = pool.get(&(len + 1));
+                assert!(first.capacity() >= len);
+                assert!(second.capacity() > len);
+            });
+        }
+    });
+
+    assert_eq!(pool.len(), 5);
+}
+
+#[test]
+fn mapped() {
+    let pool: Pool<Invariants> = Pool::with_size(1);
+
+    let mut boxed = Recycled::map(pool.get(), |value| &mut value.boxed);
+    **boxed += 1;
+    let name = MappedRecycled::map(Recycled::map(pool.get(), |value| value), |value| {
+        &mut value.name
+    });
+    assert_eq!(*name, NAMES[0]);
+
+    let moved = vec![boxed];
+    let value = MappedRecycled::into_inner(moved.into_iter().next().unwrap());
+    assert_eq!(*value.boxed, 1);
+    drop(name);
+    assert_eq!(pool.size(), 1);
+}
+
+#[test]
+fn shared_across_threads() {
+    let pool: Arc<Pool<Invariants>> = Arc::new(Pool::with_size(1));
+
+    let shared = pool.get_shared();
+    let handle = {
+        let shared = shared.clone();
+        thread::spawn(move || *shared.boxed)
+    };
+    assert_eq!(handle.join().unwrap(), 0);
+
+    let mut value = match SharedRecycled::try_unwrap(shared) {
+        Ok(value) => value,
+        Err(_) => panic!("the thread's clone was dropped"),
+    };
+    *value.boxed = 1;
+    let shared = SharedRecycled::from(value);
+    let clone = shared.clone();
+    drop(pool);
+    drop(shared);
+    assert_eq!(*clone.boxed, 1);
+}
+
+#[test]
+fn frozen_views() {
+    let pool: Arc<Pool<Vec<u8>>> = Arc::new(Pool::new());
+
+    let mut buf = pool.get_owned();
+    buf.extend_from_slice(b"hello world");
+    let mut buf = OwnedRecycled::freeze(buf);
+    let hello = buf.split_to(6);
+    let handle = thread::spawn(move || hello.len());
+    assert_eq!(handle.join().unwrap(), 6);
+
+    drop(pool);
+    assert_eq!(&buf[..], b"world");
+
+    let pool: Pool<Vec<u8>> = Pool::new();
+    let mut buf = pool.get();
+    buf.push(1);
+    let buf = Recycled::freeze(buf);
+    let clone = buf.clone();
+    drop(buf);
+    assert_eq!(&clone[..], [1]);
+}
@@ -0,0 +1,37 @@
+//! Test for moving pooled objects across threads using `Pool::get_owned`.
+
+use std::sync::Arc;
+use std::thread;
+use swimmer::Pool;
+
+#[test]
+fn owned_values_outlive_borrow() {
+    let pool: Arc<Pool<String>> = Arc::new(Pool::with_size(4));
+
+    let handles: Vec<_> = (0..4)
+        .map(|i| {
+            let mut value = pool.get_owned();
+            thread::spawn(move || {
+                value.push_str(&i.to_string());
+                value
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        let value = handle.join().unwrap();
+        assert_eq!(value.len(), 1);
+    }
+
+    assert_eq!(pool.size(), 4);
+}
+
+#[test]
+fn owned_value_keeps_pool_alive() {
+    let pool: Arc<Pool<Vec<u8>>> = Arc::new(Pool::new());
+    let mut value = pool.get_owned();
+    drop(pool);
+
+    value.push(1);
+    drop(value);
+}
@@ -0,0 +1,68 @@
+//! Tests for filling a pool ahead of time.
+
+mod common;
+
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::thread;
+use swimmer::Pool;
+
+#[test]
+fn prefill_respects_limits() {
+    let pool: Pool<String> = swimmer::builder()
+        .with_max_size(8)
+        .with_max_total_size(12)
+        .build();
+
+    pool.prefill_local(16);
+    assert_eq!(pool.local_size(), 8);
+
+    pool.reserve(16);
+    assert_eq!(pool.global_size(), 4);
+    assert_eq!(pool.size(), 12);
+
+    // Already filled
+    pool.prefill_local(4);
+    assert_eq!(pool.size(), 12);
+}
+
+#[test]
+fn threads_are_warmed_up() {
+    let (builder, created) = common::counting_builder();
+    let pool: Arc<Pool<Vec<u8>>> = Arc::new(builder.with_thread_starting_size(4).build());
+
+    for _ in 0..4 {
+        let handle = {
+            let pool = Arc::clone(&pool);
+            thread::spawn(move || {
+                drop(pool.get());
+                pool.local_size()
+            })
+        };
+        assert_eq!(handle.join().unwrap(), 4);
+    }
+
+    // Each thread reused the objects of the
+    // previous one, which it left behind on exit.
+    assert_eq!(created.load(Ordering::SeqCst), 4);
+    assert_eq!(pool.global_size(), 4);
+}
+
+#[test]
+fn warm_up_waits_for_first_checkout() {
+    let pool: Arc<Pool<String>> = Arc::new(swimmer::builder().with_thread_starting_size(8).build());
+
+    let other = Arc::clone(&pool);
+    let sizes = thread::spawn(move || {
+        other.clear();
+        let cleared = other.size();
+        other.evict_idle();
+        let local_size = other.local_size();
+
+        drop(other.get());
+        (cleared, local_size, other.local_size())
+    })
+    .join()
+    .unwrap();
+    assert_eq!(sizes, (0, 0, 8));
+}
@@ -0,0 +1,141 @@
+//! Tests for returning objects which are dropped on another thread.
+
+mod common;
+
+use std::sync::atomic::Ordering;
+use std::sync::{mpsc, Arc};
+use std::thread;
+use swimmer::{Pool, ReturnPolicy};
+
+#[test]
+fn objects_are_returned_to_producer() {
+    let (builder, created) = common::counting_builder();
+    let pool: Arc<Pool<Vec<u8>>> =
+        Arc::new(builder.with_return_policy(ReturnPolicy::Origin).build());
+
+    let (tx, rx) = mpsc::sync_channel(0);
+    let (done_tx, done_rx) = mpsc::channel();
+    let consumer = {
+        let pool = Arc::clone(&pool);
+        thread::spawn(move || {
+            for value in rx {
+                drop(value);
+                done_tx.send(()).unwrap();
+            }
+            pool.local_size()
+        })
+    };
+
+    for _ in 0..64 {
+        tx.send(pool.get_owned()).unwrap();
+        done_rx.recv().unwrap();
+    }
+    drop(tx);
+
+    // The consumer kept none of the objects,
+    // and the producer kept reusing the same one.
+    assert_eq!(consumer.join().unwrap(), 0);
+    assert_eq!(created.load(Ordering::SeqCst), 1);
+    assert_eq!(pool.size(), 1);
+}
+
+#[test]
+fn objects_of_exited_threads_go_to_global_buffer() {
+    let pool: Arc<Pool<String>> = Arc::new(
+        swimmer::builder()
+            .with_return_policy(ReturnPolicy::Origin)
+            .build(),
+    );
+    // Make sure that the other thread doesn't
+    // reuse this thread's buffer, or vice versa.
+    assert_eq!(pool.local_size(), 0);
+
+    let string = {
+        let pool = Arc::clone(&pool);
+        thread::spawn(move || pool.get_owned()).join().unwrap()
+    };
+    drop(string);
+
+    assert_eq!(pool.local_size(), 0);
+    assert_eq!(pool.global_size(), 1);
+}
+
+#[test]
+fn global_policy() {
+    let pool: Pool<String> = swimmer::builder()
+        .with_return_policy(ReturnPolicy::Global)
+        .build();
+    assert_eq!(pool.return_policy(), ReturnPolicy::Global);
+
+    drop(pool.get_many(4));
+    assert_eq!(pool.local_size(), 0);
+    assert_eq!(pool.global_size(), 4);
+}
+
+#[test]
+fn batches_follow_return_policy() {
+    let pool: Pool<String> = swimmer::builder()
+        .with_return_policy(ReturnPolicy::Global)
+        .build();
+
+    pool.recycle_all(pool.get_many(4));
+    assert_eq!(pool.local_size(), 0);
+    assert_eq!(pool.global_size(), 4);
+
+    let pool: Pool<String> = swimmer::builder()
+        .with_return_policy(ReturnPolicy::Origin)
+        .build();
+    // Make sure that the other thread doesn't
+    // reuse this thread's buffer, or vice versa.
+    assert_eq!(pool.local_size(), 0);
+
+    let pool = &pool;
+    thread::scope(|s| {
+        let (tx, rx) = mpsc::channel();
+        let (done_tx, done_rx) = mpsc::channel();
+        let producer = s.spawn(move || {
+            tx.send(pool.get_many(4)).unwrap();
+            done_rx.recv().unwrap();
+            pool.local_size()
+        });
+
+        // The objects go back to the producer
+        pool.recycle_all(rx.recv().unwrap());
+        assert_eq!(pool.local_size(), 0);
+        assert_eq!(pool.global_size(), 0);
+
+        done_tx.send(()).unwrap();
+        assert_eq!(producer.join().unwrap(), 4);
+    });
+}
+
+#[test]
+fn returned_objects_are_cleared() {
+    let pool: Pool<String> = swimmer::builder()
+        .with_return_policy(ReturnPolicy::Origin)
+        .build();
+    assert_eq!(pool.local_size(), 0);
+
+    let pool = &pool;
+    thread::scope(|s| {
+        let (tx, rx) = mpsc::channel();
+        let (done_tx, done_rx) = mpsc::channel();
+        let producer = s.spawn(move || {
+            tx.send(pool.get()).unwrap();
+            done_rx.recv().unwrap();
+
+            let returned = pool.local_size();
+            pool.clear();
+            (returned, pool.local_size())
+        });
+
+        drop(rx.recv().unwrap());
+        assert_eq!(pool.size(), 1);
+        let sizes: usize = pool.thread_sizes().iter().map(|(_, size)| size).sum();
+        assert_eq!(sizes, 1);
+
+        done_tx.send(()).unwrap();
+        assert_eq!(producer.join().unwrap(), (1, 0));
+    });
+    assert_eq!(pool.size(), 0);
+}
@@ -0,0 +1,78 @@
+//! Tests for reference-counted pooled objects.
+
+use std::sync::Arc;
+use std::thread;
+use swimmer::{Pool, SharedRecycled};
+
+#[test]
+fn returned_after_last_clone() {
+    let pool: Arc<Pool<Vec<u8>>> = Arc::new(Pool::new());
+
+    let mut buf = pool.get_owned();
+    buf.extend_from_slice(b"message");
+    let first = SharedRecycled::from(buf);
+    let clones: Vec<_> = (0..4).map(|_| first.clone()).collect();
+    assert!(clones
+        .iter()
+        .all(|clone| SharedRecycled::ptr_eq(clone, &first)));
+    assert_eq!(SharedRecycled::strong_count(&first), 5);
+
+    drop(first);
+    drop(clones);
+    assert_eq!(pool.size(), 1);
+    assert!(pool.get().is_empty());
+}
+
+#[test]
+fn nodes_are_reused() {
+    let pool: Arc<Pool<String>> = Arc::new(Pool::new());
+
+    let first = pool.get_shared();
+    let address = &*first as *const String;
+    drop(first);
+
+    // The object is stored in the same node again.
+    let second = pool.get_shared();
+    assert_eq!(&*second as *const String, address);
+}
+
+#[test]
+fn shared_across_threads() {
+    let pool: Arc<Pool<Vec<u32>>> = Arc::new(Pool::new());
+    pool.local_size();
+
+    let mut values = pool.get_owned();
+    values.extend(0..100);
+    let values = SharedRecycled::from(values);
+
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let values = values.clone();
+            thread::spawn(move || values.iter().sum::<u32>())
+        })
+        .collect();
+    drop(values);
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), 4950);
+    }
+
+    // The last clone was dropped by one of the threads.
+    assert_eq!(pool.size(), 1);
+}
+
+#[test]
+fn try_unwrap_and_keep_pool_alive() {
+    let pool: Arc<Pool<String>> = Arc::new(Pool::new());
+
+    let string = pool.get_shared();
+    let clone = string.clone();
+    let string = SharedRecycled::try_unwrap(string).unwrap_err();
+    drop(clone);
+    let mut string = SharedRecycled::try_unwrap(string).unwrap();
+    string.push_str("test");
+
+    let shared = SharedRecycled::from(string);
+    assert!(Arc::ptr_eq(SharedRecycled::pool(&shared), &pool));
+    drop(pool);
+    assert_eq!(*shared, "test");
+}
@@ -0,0 +1,88 @@
+//! Tests for shrinking a pool and evicting idle objects.
+
+mod common;
+
+use std::sync::atomic::Ordering;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+use swimmer::Pool;
+
+#[test]
+fn shrink_other_thread_lazily() {
+    let pool: Arc<Pool<String>> = Arc::new(swimmer::builder().with_max_size(16).build());
+
+    let (shrunk_tx, shrunk_rx) = mpsc::channel();
+    let (done_tx, done_rx) = mpsc::channel();
+    let other = {
+        let pool = Arc::clone(&pool);
+        thread::spawn(move || {
+            let values: Vec<_> = (0..8).map(|_| pool.get()).collect();
+            drop(values);
+            done_tx.send(()).unwrap();
+
+            // Using the pool again applies the requested shrink
+            shrunk_rx.recv().unwrap();
+            drop(pool.get());
+            pool.local_size()
+        })
+    };
+    done_rx.recv().unwrap();
+
+    drop(pool.get());
+    assert_eq!(pool.size(), 9);
+
+    pool.shrink_to(4);
+    assert_eq!(pool.local_size(), 0);
+    assert_eq!(pool.size(), 8);
+
+    shrunk_tx.send(()).unwrap();
+    assert_eq!(other.join().unwrap(), 4);
+    assert_eq!(pool.size(), 4);
+}
+
+#[test]
+fn idle_objects_are_evicted_lazily() {
+    use std::time::Duration;
+
+    let pool: Pool<String> = swimmer::builder()
+        .with_idle_timeout(Duration::from_millis(20))
+        .build();
+
+    let values: Vec<_> = (0..4).map(|_| pool.get()).collect();
+    drop(values);
+    assert_eq!(pool.size(), 4);
+
+    thread::sleep(Duration::from_millis(40));
+    drop(pool.get());
+
+    // The expired objects were dropped before retrieving
+    // a value, so a new one had to be initialized
+    assert_eq!(pool.size(), 1);
+}
+
+#[test]
+fn expired_objects_are_not_moved_from_global_buffer() {
+    use std::time::Duration;
+
+    let pool: Pool<String> = swimmer::builder()
+        .with_starting_size(8)
+        .with_idle_timeout(Duration::from_millis(1))
+        .build();
+
+    thread::sleep(Duration::from_millis(5));
+    drop(pool.get());
+    // Only the new object is left
+    assert_eq!(pool.size(), 1);
+
+    let (builder, created) = common::counting_builder();
+    let pool: Pool<String> = builder
+        .with_starting_size(8)
+        .with_idle_timeout(Duration::from_millis(1))
+        .build();
+
+    thread::sleep(Duration::from_millis(5));
+    drop(pool.get_many(2));
+    // Both objects had to be initialized
+    assert_eq!(created.load(Ordering::SeqCst), 10);
+}
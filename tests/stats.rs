@@ -0,0 +1,18 @@
+//! Tests for `Pool::stats`.
+#![cfg(feature = "stats")]
+
+use swimmer::Pool;
+
+#[test]
+fn failed_get_is_not_counted() {
+    let pool: Pool<String> = swimmer::builder()
+        .with_try_supplier(|| Err("no more strings"))
+        .build();
+
+    assert!(pool.try_get().is_err());
+
+    let stats = pool.stats();
+    assert_eq!(stats.gets, 0);
+    assert_eq!(stats.misses, 0);
+    assert_eq!(stats.checked_out, 0);
+}
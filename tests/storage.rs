@@ -0,0 +1,129 @@
+//! Tests for the storage strategies other than thread-local buffers.
+
+mod common;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use swimmer::{Pool, Storage};
+
+#[test]
+fn objects_from_exited_threads_are_reused() {
+    let pool: Arc<Pool<Vec<u8>>> =
+        Arc::new(swimmer::builder().with_storage(Storage::Global).build());
+
+    let handles: Vec<_> = (0..8)
+        .map(|_| {
+            let pool = Arc::clone(&pool);
+            thread::spawn(move || {
+                let values: Vec<_> = (0..16).map(|_| pool.get()).collect();
+                drop(values);
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    assert_eq!(pool.size(), pool.global_size());
+    assert!(pool.size() >= 16);
+
+    let values: Vec<_> = (0..16).map(|_| pool.get()).collect();
+    assert_eq!(pool.local_size(), 0);
+    drop(values);
+    assert_eq!(pool.global_size(), pool.size());
+}
+
+#[test]
+fn limits_and_shrinking() {
+    let pool: Pool<String> = swimmer::builder()
+        .with_storage(Storage::Global)
+        .with_starting_size(8)
+        .with_max_total_size(10)
+        .build();
+    assert_eq!(pool.storage(), Storage::Global);
+
+    let values: Vec<_> = (0..12).map(|_| pool.get()).collect();
+    drop(values);
+    assert_eq!(pool.size(), 10);
+
+    pool.shrink_to(4);
+    assert_eq!(pool.size(), 4);
+    assert_eq!(pool.global_size(), 4);
+
+    pool.clear();
+    assert_eq!(pool.size(), 0);
+}
+
+#[test]
+fn expired_objects_are_skipped() {
+    let pool: Pool<String> = swimmer::builder()
+        .with_storage(Storage::Global)
+        .with_starting_size(4)
+        .with_idle_timeout(Duration::from_millis(1))
+        .build();
+
+    thread::sleep(Duration::from_millis(5));
+    drop(pool.get());
+
+    // The starting objects were evicted,
+    // and a new object was returned.
+    assert_eq!(pool.size(), 1);
+}
+
+#[test]
+fn sharded_pool_steals_from_other_shards() {
+    let shard = Arc::new(AtomicUsize::new(0));
+    let (builder, created) = common::counting_builder();
+    let pool: Pool<String> = {
+        let shard = Arc::clone(&shard);
+        builder
+            .with_storage(Storage::Sharded(4))
+            .with_shard_key(move || shard.load(Ordering::SeqCst))
+            .build()
+    };
+
+    let values: Vec<_> = (0..4).map(|_| pool.get()).collect();
+    drop(values);
+    assert_eq!(created.load(Ordering::SeqCst), 4);
+
+    // The objects were returned to shard 0,
+    // so shard 1 has to steal them.
+    shard.store(1, Ordering::SeqCst);
+    let values: Vec<_> = (0..4).map(|_| pool.get()).collect();
+    assert_eq!(created.load(Ordering::SeqCst), 4);
+    assert_eq!(pool.size(), 0);
+    drop(values);
+
+    assert_eq!(pool.size(), 4);
+    assert_eq!(pool.global_size(), 4);
+}
+
+#[test]
+fn sharded_pool_across_threads() {
+    let pool: Arc<Pool<Vec<u8>>> = Arc::new(
+        swimmer::builder()
+            .with_storage(Storage::Sharded(3))
+            .with_starting_size(6)
+            .build(),
+    );
+
+    let handles: Vec<_> = (0..6)
+        .map(|_| {
+            let pool = Arc::clone(&pool);
+            thread::spawn(move || {
+                for _ in 0..64 {
+                    pool.get().push(1);
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    assert_eq!(pool.size(), pool.global_size());
+    assert!(pool.size() >= 6);
+    assert!(pool.get().is_empty());
+}
@@ -0,0 +1,86 @@
+//! Tests for reclaiming the objects of threads which have exited.
+
+mod common;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use swimmer::Pool;
+
+#[test]
+fn short_lived_threads_reuse_objects() {
+    let (builder, created) = common::counting_builder();
+    let pool: Arc<Pool<Vec<u8>>> = Arc::new(builder.build());
+
+    for _ in 0..32 {
+        let handle = {
+            let pool = Arc::clone(&pool);
+            thread::spawn(move || {
+                let values: Vec<_> = (0..4).map(|_| pool.get()).collect();
+                drop(values);
+            })
+        };
+        handle.join().unwrap();
+
+        assert_eq!(pool.size(), 4);
+        assert_eq!(pool.global_size(), 4);
+    }
+
+    assert_eq!(created.load(Ordering::SeqCst), 4);
+}
+
+#[test]
+fn pool_dropped_before_thread_exits() {
+    let dropped = Arc::new(AtomicUsize::new(0));
+
+    let handle = {
+        let dropped = Arc::clone(&dropped);
+        thread::spawn(move || {
+            for _ in 0..4 {
+                let dropped = Arc::clone(&dropped);
+                let pool: Pool<String> = swimmer::builder()
+                    .with_drop_hook(move |_| {
+                        dropped.fetch_add(1, Ordering::SeqCst);
+                    })
+                    .build();
+
+                drop(pool.get());
+                assert_eq!(pool.local_size(), 1);
+            }
+        })
+    };
+    handle.join().unwrap();
+
+    assert_eq!(dropped.load(Ordering::SeqCst), 4);
+}
+
+#[test]
+fn objects_of_exited_threads_are_dropped_with_pool() {
+    let dropped = Arc::new(AtomicUsize::new(0));
+    let pool: Arc<Pool<String>> = {
+        let dropped = Arc::clone(&dropped);
+        Arc::new(
+            swimmer::builder()
+                .with_drop_hook(move |_| {
+                    dropped.fetch_add(1, Ordering::SeqCst);
+                })
+                .build(),
+        )
+    };
+
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let pool = Arc::clone(&pool);
+            thread::spawn(move || drop(pool.get()))
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    let size = pool.global_size();
+    assert_eq!(pool.size(), size);
+
+    drop(pool);
+    assert_eq!(dropped.load(Ordering::SeqCst), size);
+}